@@ -0,0 +1,10 @@
+//! The field math, with nothing but `core`,
+//! so that it can be shared with shaders and embedded targets.
+
+#![cfg_attr(not(test), no_std)]
+
+mod particle;
+mod real;
+
+pub use particle::*;
+pub use real::*;
@@ -0,0 +1,199 @@
+use glam::{DVec2, DVec3, Vec2, Vec3};
+
+use crate::{Real, Vector};
+
+// USEFUL CONSTANTS
+pub const COULOMB: f32 = 8.987_552e9;
+pub const GRAVITATIONAL: f32 = 6.674_302e-11;
+
+/// Something that can be sampled across the plane.
+///
+/// Positions are [`Vec2`] unless asked for in another precision, such as [`DVec2`].
+pub trait Field<T, P = Vec2> {
+    fn at(&self, pos: P) -> T;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Distance<F = f32>(pub F);
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Voltage<F = f32>(pub F);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Potential<V = Vec2>(pub V);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Force<V = Vec2>(pub V);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle<F: Real = f32> {
+    pub value: F,
+    pub radius: F,
+    /// Particles can sit anywhere in space,
+    /// but the field is only ever viewed through the `z = 0` plane.
+    pub pos: F::Vec3,
+}
+
+impl<F: Real> Default for Particle<F> {
+    fn default() -> Self {
+        Self {
+            value: F::ONE,
+            radius: F::ONE,
+            pos: Default::default(),
+        }
+    }
+}
+
+impl<F: Real> Particle<F> {
+    /// A particle sitting in the `z = 0` plane.
+    ///
+    /// The precision follows `pos`, so a [`DVec2`] makes a `Particle<f64>`.
+    #[inline]
+    pub fn new<V: Vector<Real = F>>(value: F, radius: F, pos: V) -> Self {
+        Self::new_3d(value, radius, pos.extend(F::ZERO))
+    }
+
+    #[inline]
+    pub fn new_3d(value: F, radius: F, pos: F::Vec3) -> Self {
+        Self { value, radius, pos }
+    }
+}
+
+/// The physics, written once for each precision.
+macro_rules! particle {
+    ($f:ty, $vec2:ty, $vec3:ty) => {
+        impl Field<Distance<$f>, $vec2> for &[Particle<$f>] {
+            fn at(&self, pos: $vec2) -> Distance<$f> {
+                let mut d = Distance(<$f>::INFINITY);
+                for p in *self {
+                    d = Distance(p.dist(pos).0.min(d.0));
+                }
+                d
+            }
+        }
+
+        impl Field<Voltage<$f>, $vec2> for &[Particle<$f>] {
+            fn at(&self, pos: $vec2) -> Voltage<$f> {
+                let mut v = Voltage(0.0);
+                for p in *self {
+                    v.0 += p.voltage(pos).0;
+                }
+                v
+            }
+        }
+
+        impl Field<Potential<$vec2>, $vec2> for &[Particle<$f>] {
+            fn at(&self, pos: $vec2) -> Potential<$vec2> {
+                let mut v = Potential(<$vec2>::ZERO);
+                for p in *self {
+                    match p.potential(pos) {
+                        Ok(x) => v.0 += x.0,
+                        Err(x) => return x,
+                    }
+                }
+                v
+            }
+        }
+
+        impl Field<Force<$vec2>, $vec2> for &[Particle<$f>] {
+            fn at(&self, pos: $vec2) -> Force<$vec2> {
+                let mut e = Force(<$vec2>::ZERO);
+                for p in *self {
+                    // each particle is a shell of charge, with no field of its own inside
+                    if let Some(x) = p.force(pos) {
+                        e.0 += x.0;
+                    }
+                }
+                e
+            }
+        }
+
+        impl Particle<$f> {
+            /// From the centre of the particle to `pos` in the `z = 0` plane.
+            #[inline]
+            fn offset(&self, pos: $vec2) -> $vec3 {
+                pos.extend(0.0) - self.pos
+            }
+
+            pub fn dist(&self, pos: $vec2) -> Distance<$f> {
+                Distance(self.offset(pos).length() - self.radius)
+            }
+
+            /// Scalar potential, `V = q / r`.
+            ///
+            /// Inside the radius the particle behaves like a charged conducting sphere,
+            /// so the potential stays at its surface value.
+            pub fn voltage(&self, pos: $vec2) -> Voltage<$f> {
+                let r = self.offset(pos).length().max(self.radius);
+                Voltage(self.value / r)
+            }
+
+            pub fn potential(&self, pos: $vec2) -> Result<Potential<$vec2>, Potential<$vec2>> {
+                let vec = self.offset(pos);
+                if self.dist(pos).0 >= 0.0 {
+                    let r = vec.length();
+                    Ok(Potential(vec.truncate() * self.value / (r * r)))
+                } else {
+                    let r = self.radius;
+                    Err(Potential(vec.truncate() * self.value / (r * r)))
+                }
+            }
+
+            /// The part of the field lying in the `z = 0` plane.
+            pub fn force(&self, pos: $vec2) -> Option<Force<$vec2>> {
+                let vec = self.offset(pos);
+                if self.dist(pos).0 >= 0.0 {
+                    let r = vec.length();
+                    Some(Force(vec.truncate() * self.value / (r * r * r)))
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+particle!(f32, Vec2, Vec3);
+particle!(f64, DVec2, DVec3);
+
+impl Particle {
+    /// The same particle, worked out in double precision.
+    pub fn as_f64(&self) -> Particle<f64> {
+        Particle::new_3d(self.value as f64, self.radius as f64, self.pos.as_dvec3())
+    }
+}
+
+#[test]
+fn inside_a_particle() {
+    let particles = [
+        Particle::new(1.0, 0.5, Vec2::ZERO),
+        Particle::new(2.0, 0.1, Vec2::new(2.0, 0.0)),
+    ];
+    let pos = Vec2::new(0.1, 0.0);
+    // the particle around `pos` adds nothing, the other still pulls
+    let e = Field::<Force>::at(&&particles[..], pos);
+    assert_eq!(Some(e), particles[1].force(pos));
+}
+
+#[test]
+fn precision() {
+    // a big charge seen from far away, where f32 can't tell nearby points apart
+    let particles = [Particle::new(1e9, 1.0, Vec2::ZERO)];
+    let doubles = particles.map(|p| p.as_f64());
+    let (q, a, b) = (1e9, 1000.0, 1_000.000_01);
+
+    let v = |x: f64| Field::<Voltage>::at(&&particles[..], Vec2::new(x as f32, 0.0)).0 as f64;
+    let v64 = |x: f64| Field::<Voltage<f64>, DVec2>::at(&&doubles[..], DVec2::new(x, 0.0)).0;
+    // both agree on the potential itself
+    assert!(((v(a) - v64(a)) / v64(a)).abs() < 1e-6);
+    // but only f64 gets the difference right
+    let exact = q / b - q / a;
+    assert!(((v64(b) - v64(a)) - exact).abs() < 1e-6 * exact.abs());
+    assert!(((v(b) - v(a)) - exact).abs() > 1e-2 * exact.abs());
+
+    let Force(e) = Field::<Force<DVec2>, DVec2>::at(&&doubles[..], DVec2::new(a, 0.0));
+    let Force(e32) = Field::<Force>::at(&&particles[..], Vec2::new(a as f32, 0.0));
+    assert!((e.x - q / (a * a)).abs() < 1e-9);
+    assert!((e32.as_dvec2() - e).length() < 1e-3 * e.length());
+}
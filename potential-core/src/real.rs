@@ -0,0 +1,69 @@
+use core::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use glam::{DVec2, DVec3, Vec2, Vec3};
+
+/// The precision things are worked out in.
+///
+/// `f32` is fast and what the app draws with, but with charges spread over
+/// many orders of magnitude `f64` can be needed to avoid banding.
+pub trait Real:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + 'static
+{
+    /// The glam vectors of the same precision.
+    type Vec2: Vector<Real = Self>;
+    type Vec3: Copy + Debug + Default + PartialEq;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_u32(x: u32) -> Self;
+}
+
+/// A glam 2D vector, [`Vec2`] or [`DVec2`].
+pub trait Vector: Copy + Debug + Default + PartialEq {
+    type Real: Real<Vec2 = Self>;
+
+    fn extend(self, z: Self::Real) -> <Self::Real as Real>::Vec3;
+}
+
+macro_rules! real {
+    ($f:ty, $vec2:ty, $vec3:ty) => {
+        impl Real for $f {
+            type Vec2 = $vec2;
+            type Vec3 = $vec3;
+
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+
+            #[inline]
+            fn from_u32(x: u32) -> Self {
+                x as $f
+            }
+        }
+
+        impl Vector for $vec2 {
+            type Real = $f;
+
+            #[inline]
+            fn extend(self, z: $f) -> $vec3 {
+                <$vec2>::extend(self, z)
+            }
+        }
+    };
+}
+
+real!(f32, Vec2, Vec3);
+real!(f64, DVec2, DVec3);
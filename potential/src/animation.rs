@@ -0,0 +1,269 @@
+//! Particles whose parameters change over time.
+
+use core::f32::consts::TAU;
+
+use glam::Vec3;
+
+use crate::{
+    expr::{Expr, ParseError},
+    Particle,
+};
+
+/// A value that changes with time, in seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    Sine {
+        offset: f32,
+        amplitude: f32,
+        /// In Hz.
+        frequency: f32,
+        /// In radians.
+        phase: f32,
+    },
+    /// Moves linearly from `from` to `to`, holding either side.
+    Ramp {
+        from: f32,
+        to: f32,
+        start: f32,
+        duration: f32,
+    },
+    /// `(time, value)` pairs, linearly interpolated.
+    /// Must be sorted by time.
+    Keyframes(Vec<(f32, f32)>),
+    Expression(Expression),
+}
+
+/// An expression of `t`, kept as it was written.
+///
+/// It's parsed whenever the text changes, rather than every time it's evaluated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    text: String,
+    parsed: Result<Expr, ParseError>,
+}
+
+impl Expression {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            parsed: text.parse(),
+            text,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        if text != self.text {
+            *self = Self::new(text);
+        }
+    }
+
+    /// Value at time `t`, as long as it parsed and uses nothing but `t`.
+    pub fn at(&self, t: f32) -> Option<f32> {
+        let expr = self.parsed.as_ref().ok()?;
+        expr.eval(&[("t", t)]).ok()
+    }
+
+    /// What's wrong with the expression, if anything.
+    pub fn error(&self) -> Option<String> {
+        match &self.parsed {
+            Ok(expr) => expr.eval(&[("t", 0.0)]).err().map(|e| e.to_string()),
+            Err(e) => Some(e.to_string()),
+        }
+    }
+}
+
+impl Signal {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Sine { .. } => "Sine",
+            Signal::Ramp { .. } => "Ramp",
+            Signal::Keyframes(_) => "Keyframes",
+            Signal::Expression(_) => "Expression",
+        }
+    }
+
+    /// Value at time `t`.
+    ///
+    /// There's nothing to go on for an expression that doesn't parse,
+    /// or uses anything but `t`, or keyframes without any keys.
+    pub fn at(&self, t: f32) -> Option<f32> {
+        let v = match self {
+            Signal::Sine {
+                offset,
+                amplitude,
+                frequency,
+                phase,
+            } => offset + amplitude * (TAU * frequency * t + phase).sin(),
+            Signal::Ramp {
+                from,
+                to,
+                start,
+                duration,
+            } => {
+                let f = if *duration > 0.0 {
+                    ((t - start) / duration).clamp(0.0, 1.0)
+                } else if t < *start {
+                    0.0
+                } else {
+                    1.0
+                };
+                from + (to - from) * f
+            }
+            Signal::Keyframes(keys) => match keys.iter().position(|&(k, _)| k > t) {
+                None => return keys.last().map(|&(_, v)| v),
+                Some(0) => keys[0].1,
+                Some(i) => {
+                    let (t0, v0) = keys[i - 1];
+                    let (t1, v1) = keys[i];
+                    v0 + (v1 - v0) * (t - t0) / (t1 - t0)
+                }
+            },
+            Signal::Expression(expression) => return expression.at(t),
+        };
+        Some(v)
+    }
+}
+
+/// Which parameters of a particle are animated.
+/// Anything left as `None` keeps the particle's own value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Track {
+    pub value: Option<Signal>,
+    pub radius: Option<Signal>,
+    pub pos: [Option<Signal>; 3],
+}
+
+impl Track {
+    pub fn is_constant(&self) -> bool {
+        self.value.is_none() && self.radius.is_none() && self.pos.iter().all(Option::is_none)
+    }
+
+    /// The particle at time `t`.
+    /// A signal with nothing to go on leaves the particle's own value.
+    pub fn apply(&self, p: &Particle, t: f32) -> Particle {
+        let get = |s: &Option<Signal>, base: f32| s.as_ref().and_then(|s| s.at(t)).unwrap_or(base);
+        Particle {
+            value: get(&self.value, p.value),
+            radius: get(&self.radius, p.radius),
+            pos: Vec3::new(
+                get(&self.pos[0], p.pos.x),
+                get(&self.pos[1], p.pos.y),
+                get(&self.pos[2], p.pos.z),
+            ),
+        }
+    }
+}
+
+/// Playback of the animation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timeline {
+    /// In seconds.
+    pub time: f32,
+    pub duration: f32,
+    pub speed: f32,
+    pub playing: bool,
+    /// Start over at the end, rather than stopping.
+    pub looping: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            duration: 10.0,
+            speed: 1.0,
+            playing: false,
+            looping: true,
+        }
+    }
+}
+
+impl Timeline {
+    /// Moves time along by `dt` seconds of real time, if playing.
+    pub fn advance(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        self.time += dt * self.speed;
+        if self.time >= self.duration {
+            if self.looping && self.duration > 0.0 {
+                self.time %= self.duration;
+            } else {
+                self.time = self.duration;
+                self.playing = false;
+            }
+        }
+    }
+}
+
+#[test]
+fn signals() {
+    let ramp = Signal::Ramp {
+        from: 1.0,
+        to: 3.0,
+        start: 1.0,
+        duration: 2.0,
+    };
+    assert_eq!(
+        [0.0, 2.0, 5.0].map(|t| ramp.at(t)),
+        [Some(1.0), Some(2.0), Some(3.0)]
+    );
+
+    let keys = Signal::Keyframes(vec![(1.0, 0.0), (2.0, 10.0), (4.0, 0.0)]);
+    assert_eq!(
+        [0.0, 1.5, 3.0, 9.0].map(|t| keys.at(t)),
+        [Some(0.0), Some(5.0), Some(5.0), Some(0.0)]
+    );
+    assert_eq!(Signal::Keyframes(Vec::new()).at(0.0), None);
+
+    let sine = Signal::Sine {
+        offset: 1.0,
+        amplitude: 2.0,
+        frequency: 0.25,
+        phase: 0.0,
+    };
+    assert!((sine.at(1.0).unwrap() - 3.0).abs() < 1e-6);
+
+    let mut expression = Expression::new("2 * t");
+    assert_eq!(expression.at(3.0), Some(6.0));
+    expression.set_text("2 * x".to_owned());
+    assert_eq!(expression.at(3.0), None);
+    assert!(expression.error().is_some());
+    expression.set_text("2 * (t".to_owned());
+    assert!(expression.error().is_some());
+
+    // half typed, the particle stays where it was
+    let typing = Track {
+        radius: Some(Signal::Expression(expression)),
+        ..Default::default()
+    };
+    let p = Particle::new(1.0, 0.5, glam::Vec2::ZERO);
+    assert_eq!(typing.apply(&p, 1.0), p);
+
+    // a dipole swinging along x, everything else left alone
+    let track = Track {
+        pos: [Some(sine), None, None],
+        ..Default::default()
+    };
+    let p = track.apply(&Particle::new(-1.0, 0.5, glam::Vec2::new(9.0, 2.0)), 1.0);
+    assert!((p.pos - Vec3::new(3.0, 2.0, 0.0)).length() < 1e-6);
+    assert_eq!((p.value, p.radius), (-1.0, 0.5));
+}
+
+#[test]
+fn timeline() {
+    let mut timeline = Timeline {
+        playing: true,
+        ..Default::default()
+    };
+    timeline.advance(12.5);
+    assert_eq!(timeline.time, 2.5);
+    timeline.looping = false;
+    timeline.advance(20.0);
+    assert_eq!(timeline.time, 10.0);
+    assert!(!timeline.playing);
+}
@@ -1,10 +1,38 @@
+mod animation;
 mod appearance;
+mod export;
+mod file;
+mod formula;
+mod graph;
+mod history;
+mod number;
+mod profile;
+mod radial;
+mod select;
 
 use anyhow::Result;
 use archie::wgpu;
 use archie_egui::egui;
 
-use potential::{graph::Figure, Particle};
+use potential::{
+    animation::{Timeline, Track},
+    graph::Figure,
+    history::{Command, Document, History},
+    scenario::Scenario,
+    scientific::{Exponent, Format, Sci},
+    units::{Charge, ElectricPotential, Length, SurfaceCharge},
+    Geometry, Particle, ParticleUnits,
+};
+
+use export::Export;
+use file::FileMenu;
+use formula::FormulaEditor;
+use graph::{Graph, Source};
+use history::{edit_menu, history_ui};
+use number::number_edit;
+use profile::Profile;
+use radial::RadialView;
+use select::Selection;
 
 #[derive(PartialEq)]
 enum Tab {
@@ -15,13 +43,28 @@ enum Tab {
 struct WindowsOpen {
     settings: bool,
     timings: bool,
+    profile: bool,
+    radial: bool,
+    export: bool,
+    history: bool,
 }
 
 pub struct App {
     gui: archie_egui::Egui,
     tab: Tab,
-    figure: Figure,
+    geometry: Geometry,
+    graph: Graph,
+    profile: Profile,
+    radial: RadialView,
+    export: Export,
+    file: FileMenu,
     particles: Vec<Particle>,
+    /// How each particle changes over time, kept alongside `particles`.
+    tracks: Vec<Track>,
+    timeline: Timeline,
+    history: History,
+    selection: Selection,
+    formula: FormulaEditor,
     open: WindowsOpen,
 }
 
@@ -31,34 +74,138 @@ impl App {
         gui.context().set_style(appearance::style());
         gui.context().set_fonts(appearance::fonts());
 
-        let app = App {
+        let figure = Figure::new(100.0, 100.0);
+        #[cfg_attr(not(target_arch = "wasm32"), allow(unused_mut))]
+        let mut app = App {
             gui,
             tab: Tab::Graph,
-            figure: Figure::new(100.0, 100.0),
+            geometry: Geometry::Points,
+            profile: Profile::new(&figure),
+            radial: RadialView::new(),
+            export: Export::new(),
+            file: FileMenu::new(),
+            graph: Graph::new(figure),
             particles: vec![
                 Particle::new(1.0, 2.0, glam::Vec2::new(1.0, 1.0)),
                 Particle::new(1.0, 2.0, glam::Vec2::new(1.0, 1.0)),
             ],
+            tracks: Vec::new(),
+            timeline: Timeline::default(),
+            history: History::default(),
+            selection: Selection::new(),
+            formula: FormulaEditor::new(),
             open: WindowsOpen {
                 settings: false,
                 timings: false,
+                profile: false,
+                radial: false,
+                export: false,
+                history: false,
             },
         };
+        // shared links open the scenario they were made from
+        #[cfg(target_arch = "wasm32")]
+        if let Some(scenario) = file::linked() {
+            app.load(scenario);
+        }
         Ok(app)
     }
+
+    /// Everything needed to set the app up like this again.
+    fn scenario(&self) -> Scenario {
+        let mut scenario = Scenario::new(self.geometry, &self.graph.figure, &self.particles);
+        scenario.formula = Some(self.formula.saved());
+        scenario.view = self.graph.view();
+        scenario
+    }
+
+    fn load(&mut self, scenario: Scenario) {
+        self.geometry = scenario.geometry;
+        scenario.figure.apply(&mut self.graph.figure);
+        self.particles = scenario.particles();
+        // the animations and history belonged to the old particles
+        self.tracks.clear();
+        self.history.clear();
+        if let Some(formula) = &scenario.formula {
+            self.formula.load(formula);
+        }
+        self.graph.set_view(&scenario.view);
+    }
 }
 
-use egui::{
-    style::Margin, CentralPanel, DragValue, Frame, Layout, RichText, TopBottomPanel, Window,
-};
+use egui::{style::Margin, CentralPanel, Frame, Key, Layout, RichText, TopBottomPanel, Window};
 
 impl archie::event::EventHandler for App {
     fn update(&mut self, ctx: &archie::Context) {
         let timer = ctx.timer();
         let avg = timer.average().as_secs_f64();
 
+        let current = self.scenario();
+        let mut opened = None;
         self.gui.update(ctx, |gui| {
-            title_bar(gui, &mut self.tab, &mut self.open);
+            let mut show_history = false;
+            opened = title_bar(
+                gui,
+                &mut self.tab,
+                &mut self.geometry,
+                &mut self.open,
+                |ui| {
+                    let opened = self.file.ui(ui, &current);
+                    let mut doc = Document {
+                        particles: &mut self.particles,
+                        tracks: &mut self.tracks,
+                        figure: &mut self.graph.figure,
+                    };
+                    show_history = edit_menu(ui, &mut self.history, &mut doc);
+                    opened
+                },
+            );
+            self.open.history |= show_history;
+
+            // text boxes have their own undo
+            let typing = gui.memory().focus().is_some();
+            let (undo, redo) = {
+                let input = gui.input();
+                let (command, shift) = (input.modifiers.command, input.modifiers.shift);
+                let z = !typing && command && input.key_pressed(Key::Z);
+                let y = !typing && command && input.key_pressed(Key::Y);
+                (z && !shift, (z && shift) || y)
+            };
+            {
+                let mut doc = Document {
+                    particles: &mut self.particles,
+                    tracks: &mut self.tracks,
+                    figure: &mut self.graph.figure,
+                };
+                if undo {
+                    self.history.undo(&mut doc);
+                }
+                if redo {
+                    self.history.redo(&mut doc);
+                }
+                Window::new("History")
+                    .open(&mut self.open.history)
+                    .resizable(false)
+                    .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
+                    .show(gui, |ui| history_ui(ui, &mut self.history, &mut doc));
+            }
+            // anything changed from here on is recorded at the end
+            let (before, figure_before) = (self.particles.clone(), self.graph.figure);
+            let mut command = None;
+
+            self.tracks
+                .resize_with(self.particles.len(), Default::default);
+            if self.timeline.playing {
+                self.timeline.advance(gui.input().unstable_dt);
+                gui.request_repaint();
+            }
+            let time = self.timeline.time;
+            let animated: Vec<Particle> = self
+                .particles
+                .iter()
+                .zip(&self.tracks)
+                .map(|(p, track)| track.apply(p, time))
+                .collect();
 
             Window::new("Timings")
                 .open(&mut self.open.timings)
@@ -88,28 +235,213 @@ impl archie::event::EventHandler for App {
 
             match self.tab {
                 Tab::Graph => {
+                    let geometry = self.geometry;
+                    let changing = match geometry {
+                        Geometry::Formula => self.formula.uses_time(),
+                        _ => self.tracks.iter().any(|t| !t.is_constant()),
+                    };
+                    if changing {
+                        TopBottomPanel::bottom("Timeline")
+                            .frame(
+                                Frame::window(&gui.style())
+                                    .margin(Margin::same(4.0))
+                                    .multiply_with_opacity(0.5),
+                            )
+                            .show(gui, |ui| animation::timeline_ui(ui, &mut self.timeline));
+                    }
                     Window::new("Settings")
                         .open(&mut self.open.settings)
                         .resizable(false)
                         .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
                         .show(gui, |ui| {
-                            if ui.button("Timings").clicked() {
-                                self.open.timings = true;
-                            }
-                            figure_ui(ui, &mut self.figure);
+                            ui.horizontal(|ui| {
+                                if ui.button("Timings").clicked() {
+                                    self.open.timings = true;
+                                }
+                                if geometry != Geometry::Sheets && ui.button("Line cut").clicked() {
+                                    self.open.profile = true;
+                                }
+                                if geometry == Geometry::Points && ui.button("Radial").clicked() {
+                                    self.open.radial = true;
+                                }
+                                if geometry != Geometry::Sheets && ui.button("Export").clicked() {
+                                    self.open.export = true;
+                                }
+                                if geometry != Geometry::Sheets
+                                    && ui.button("Zoom to fit").clicked()
+                                {
+                                    let shown = match geometry {
+                                        Geometry::Points => self.graph.slice.local(&animated),
+                                        _ => Vec::new(),
+                                    };
+                                    self.graph.zoom_to_fit(&shown);
+                                }
+                            });
+                            self.graph.settings_ui(ui, geometry);
                         });
+                    match geometry {
+                        Geometry::Points | Geometry::Formula => {
+                            let source = match geometry {
+                                Geometry::Formula => Source::Formula(self.formula.at(time)),
+                                _ => Source::Particles(self.graph.slice.local(&animated)),
+                            };
+                            Window::new("Line cut")
+                                .open(&mut self.open.profile)
+                                .resizable(false)
+                                .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
+                                .show(gui, |ui| {
+                                    self.profile.ui(ui, &self.graph.figure, &source);
+                                });
+                            if geometry == Geometry::Points {
+                                Window::new("Radial")
+                                    .open(&mut self.open.radial)
+                                    .resizable(false)
+                                    .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
+                                    .show(gui, |ui| self.radial.ui(ui, source.particles()));
+                            }
+                            Window::new("Export")
+                                .open(&mut self.open.export)
+                                .resizable(false)
+                                .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
+                                .show(gui, |ui| {
+                                    let formula = self.formula.saved();
+                                    self.export.ui(ui, &self.graph, &source, &formula)
+                                });
+                            CentralPanel::default()
+                                .frame(Frame::none())
+                                .show(gui, |ui| {
+                                    let (response, edit) = self.graph.ui(ui, &source, &self.tracks);
+                                    if let Some(edit) = edit {
+                                        command = edit.apply(
+                                            &self.graph.slice,
+                                            &self.graph.figure,
+                                            &mut self.particles,
+                                            &self.tracks,
+                                        );
+                                    }
+                                    if self.open.profile {
+                                        self.graph.cut_ui(ui, response.rect, &self.profile.cut);
+                                    }
+                                });
+                        }
+                        Geometry::Sheets => {
+                            CentralPanel::default()
+                                .frame(Frame::none().margin(Margin::same(4.0)))
+                                .show(gui, |ui| self.graph.sheets_ui(ui, &animated));
+                        }
+                    }
                 }
                 Tab::Editor => {
                     CentralPanel::default()
                         .frame(Frame::none().margin(Margin::same(4.0)))
                         .show(gui, |ui| {
-                            for (i, p) in self.particles.iter_mut().enumerate() {
-                                particle_window(gui, ui, p, i);
+                            if self.geometry == Geometry::Formula {
+                                Window::new("Formula")
+                                    .title_bar(false)
+                                    .resizable(false)
+                                    .show(gui, |ui| self.formula.ui(ui));
+                                return;
+                            }
+                            let name = match self.geometry {
+                                Geometry::Sheets => "Add sheet",
+                                _ => "Add particle",
+                            };
+                            if ui.button(name).clicked() {
+                                let f = &self.graph.figure;
+                                let centre = glam::Vec2::new(
+                                    0.5 * (f.x_min + f.x_max),
+                                    0.5 * (f.y_min + f.y_max),
+                                );
+                                let (width, _) = f.extent();
+                                let particle = Particle::with_units(
+                                    Charge(1.0),
+                                    0.05 * width,
+                                    centre.extend(0.0),
+                                );
+                                command = Some(Command::Add {
+                                    index: self.particles.len(),
+                                    particle,
+                                    track: Track::default(),
+                                });
+                            }
+
+                            self.selection.retain(self.particles.len());
+                            let doc = Document {
+                                particles: &mut self.particles,
+                                tracks: &mut self.tracks,
+                                figure: &mut self.graph.figure,
+                            };
+                            if !typing {
+                                let input = ui.input();
+                                if let Some(c) = self.selection.shortcuts(&input, &doc) {
+                                    command = Some(c);
+                                }
+                            }
+                            if !self.selection.is_empty() {
+                                let selected = Window::new("Selection")
+                                    .resizable(false)
+                                    .frame(Frame::window(&gui.style()).multiply_with_opacity(0.5))
+                                    .show(gui, |ui| self.selection.ui(ui, &doc));
+                                if let Some(c) = selected.and_then(|r| r.inner).flatten() {
+                                    command = Some(c);
+                                }
+                            }
+                            self.selection
+                                .canvas_ui(ui, &self.graph.figure, &self.particles);
+
+                            let mut remove = None;
+                            let particles = self.particles.iter_mut().zip(&mut self.tracks);
+                            for (i, (p, track)) in particles.enumerate() {
+                                let selected = self.selection.contains(i);
+                                if particle_window(gui, ui, p, track, i, selected, self.geometry) {
+                                    remove = Some(i);
+                                }
+                            }
+                            if let Some(i) = remove {
+                                command = Some(Command::Remove {
+                                    index: i,
+                                    particle: self.particles[i],
+                                    track: self.tracks[i].clone(),
+                                });
                             }
                         });
                 }
             }
+
+            // edits made in place, a whole drag or bout of typing at a time
+            let edited = before.iter().zip(&self.particles).enumerate();
+            for (index, (&old, &new)) in edited {
+                if old != new {
+                    self.history.record(Command::Edit { index, old, new });
+                }
+            }
+            // the size it's shown at doesn't need undoing
+            let (old, new) = (&figure_before, &self.graph.figure);
+            if (old.x_axis(), old.y_axis()) != (new.x_axis(), new.y_axis()) {
+                self.history.record(Command::Figure {
+                    old: figure_before,
+                    new: self.graph.figure,
+                });
+            }
+            let moving = {
+                let input = gui.input();
+                input.pointer.any_down() || input.scroll_delta != egui::Vec2::ZERO
+            };
+            if !(typing || moving) {
+                self.history.settle();
+            }
+            if let Some(command) = command {
+                let mut doc = Document {
+                    particles: &mut self.particles,
+                    tracks: &mut self.tracks,
+                    figure: &mut self.graph.figure,
+                };
+                self.history.apply(command, &mut doc);
+            }
         });
+        if let Some(scenario) = opened {
+            self.load(scenario);
+        }
     }
 
     fn draw(
@@ -126,30 +458,87 @@ impl archie::event::EventHandler for App {
     }
 }
 
-fn particle_window(gui: &egui::Context, ui: &egui::Ui, p: &mut Particle, i: usize) {
+fn particle_window(
+    gui: &egui::Context,
+    ui: &egui::Ui,
+    p: &mut Particle,
+    track: &mut Track,
+    i: usize,
+    selected: bool,
+    geometry: Geometry,
+) -> bool {
+    let mut remove = false;
     Window::new("Particle")
         .title_bar(false)
         .resizable(false)
         .id(ui.id().with(format!("Particle_{}", i)))
         .show(gui, |ui| {
-            ui.heading("Particle");
-            ui.horizontal(|ui| {
-                ui.label("Value ");
-                ui.add(DragValue::new(&mut p.value).suffix(" v"));
-            });
-            ui.horizontal(|ui| {
-                ui.label("Radius");
-                ui.add(DragValue::new(&mut p.radius).suffix(" m"));
-            });
-            ui.label("Pos");
-            ui.horizontal(|ui| {
-                ui.add(DragValue::new(&mut p.pos.x).prefix("x: ").suffix(" m"));
-                ui.add(DragValue::new(&mut p.pos.y).prefix("y: ").suffix(" m"));
-            });
+            match geometry {
+                Geometry::Points => {
+                    heading(ui, "Particle", selected);
+                    ui.horizontal(|ui| {
+                        ui.label("Value ");
+                        number_edit(ui, "value", &mut p.value, Charge::UNIT);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Radius");
+                        number_edit(ui, "radius", &mut p.radius, Length::UNIT);
+                    });
+                    let surface = Sci(p.surface_potential().0)
+                        .format(Format::default().exponent(Exponent::Superscript));
+                    ui.weak(format!(
+                        "{} {} at the surface",
+                        surface,
+                        ElectricPotential::UNIT
+                    ));
+                    ui.label("Pos");
+                    ui.horizontal(|ui| {
+                        ui.label("x:");
+                        number_edit(ui, "x", &mut p.pos.x, Length::UNIT);
+                        ui.label("y:");
+                        number_edit(ui, "y", &mut p.pos.y, Length::UNIT);
+                        // out of the `z = 0` plane
+                        ui.label("z:");
+                        number_edit(ui, "z", &mut p.pos.z, Length::UNIT);
+                    });
+                    let base = [p.value, p.radius, p.pos.x, p.pos.y, p.pos.z];
+                    ui.collapsing("Animate", |ui| animation::track_ui(ui, track, base));
+                }
+                Geometry::Sheets => {
+                    heading(ui, "Sheet", selected);
+                    ui.horizontal(|ui| {
+                        ui.label("Value");
+                        number_edit(ui, "value", &mut p.value, SurfaceCharge::UNIT);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Pos  ");
+                        ui.label("x:");
+                        number_edit(ui, "x", &mut p.pos.x, Length::UNIT);
+                    });
+                }
+                Geometry::Formula => unreachable!("a formula has no particles"),
+            }
+            remove = ui.button("Remove").clicked();
         });
+    remove
 }
 
-fn title_bar(gui: &egui::Context, tab: &mut Tab, window_open: &mut WindowsOpen) {
+/// Picked out in the selection colour when the particle is selected.
+fn heading(ui: &mut egui::Ui, text: &str, selected: bool) {
+    let mut text = RichText::new(text).heading();
+    if selected {
+        text = text.color(ui.visuals().selection.stroke.color);
+    }
+    ui.label(text);
+}
+
+fn title_bar(
+    gui: &egui::Context,
+    tab: &mut Tab,
+    geometry: &mut Geometry,
+    window_open: &mut WindowsOpen,
+    menus: impl FnOnce(&mut egui::Ui) -> Option<Scenario>,
+) -> Option<Scenario> {
     TopBottomPanel::top("Title Bar")
         .frame(
             Frame::window(&gui.style())
@@ -159,6 +548,7 @@ fn title_bar(gui: &egui::Context, tab: &mut Tab, window_open: &mut WindowsOpen)
         .height_range(0.0..=22.0)
         .show(gui, |ui| {
             ui.horizontal_top(|ui| {
+                let opened = menus(ui);
                 ui.selectable_value(tab, Tab::Graph, RichText::new("Graph").heading());
                 ui.selectable_value(tab, Tab::Editor, RichText::new("Editor").heading());
 
@@ -166,45 +556,13 @@ fn title_bar(gui: &egui::Context, tab: &mut Tab, window_open: &mut WindowsOpen)
                     if ui.button("⛭").clicked() {
                         window_open.settings = true;
                     }
+                    ui.selectable_value(geometry, Geometry::Sheets, "1D");
+                    ui.selectable_value(geometry, Geometry::Points, "2D");
+                    ui.selectable_value(geometry, Geometry::Formula, "V(x, y)");
                 });
+                opened
             })
-        });
-}
-
-fn figure_ui(ui: &mut egui::Ui, f: &mut Figure) {
-    let Figure {
-        x_min,
-        x_max,
-        y_min,
-        y_max,
-        ..
-    } = f;
-
-    ui.heading("Figure");
-    ui.horizontal(|ui| {
-        ui.add(
-            DragValue::new(x_min)
-                .clamp_range(f32::NEG_INFINITY..=*x_max)
-                .fixed_decimals(2),
-        );
-        ui.monospace("≤ X ≤");
-        ui.add(
-            DragValue::new(x_max)
-                .clamp_range(*x_min..=f32::INFINITY)
-                .fixed_decimals(2),
-        );
-    });
-    ui.horizontal(|ui| {
-        ui.add(
-            DragValue::new(y_min)
-                .clamp_range(f32::NEG_INFINITY..=*y_max)
-                .fixed_decimals(2),
-        );
-        ui.monospace("≤ Y ≤");
-        ui.add(
-            DragValue::new(y_max)
-                .clamp_range(*y_min..=f32::INFINITY)
-                .fixed_decimals(2),
-        );
-    });
+            .inner
+        })
+        .inner
 }
@@ -0,0 +1,179 @@
+use archie_egui::egui;
+use egui::{Color32, ComboBox, DragValue, Slider};
+
+use potential::animation::{Expression, Signal, Timeline, Track};
+
+/// Play, pause and scrub through time.
+pub(super) fn timeline_ui(ui: &mut egui::Ui, timeline: &mut Timeline) {
+    ui.horizontal(|ui| {
+        let icon = if timeline.playing { "⏸" } else { "▶" };
+        if ui.button(icon).clicked() {
+            if !timeline.playing && timeline.time >= timeline.duration {
+                timeline.time = 0.0;
+            }
+            timeline.playing = !timeline.playing;
+        }
+        if ui.button("⏮").clicked() {
+            timeline.time = 0.0;
+        }
+        ui.add(
+            Slider::new(&mut timeline.time, 0.0..=timeline.duration)
+                .suffix(" s")
+                .fixed_decimals(2),
+        );
+        ui.label("Length");
+        ui.add(
+            DragValue::new(&mut timeline.duration)
+                .clamp_range(0.1..=f32::MAX)
+                .suffix(" s")
+                .speed(0.1),
+        );
+        ui.label("Speed");
+        ui.add(
+            DragValue::new(&mut timeline.speed)
+                .clamp_range(0.0..=10.0)
+                .suffix("×")
+                .speed(0.01),
+        );
+        ui.checkbox(&mut timeline.looping, "Loop");
+    });
+}
+
+/// Editors for each parameter of a particle that can be animated.
+/// `base` holds the particle's own values, used to start a new signal from.
+pub(super) fn track_ui(ui: &mut egui::Ui, track: &mut Track, base: [f32; 5]) {
+    let Track { value, radius, pos } = track;
+    let [x, y, z] = pos;
+    let signals = [
+        ("Value", value),
+        ("Radius", radius),
+        ("x", x),
+        ("y", y),
+        ("z", z),
+    ];
+    for ((name, signal), base) in signals.into_iter().zip(base) {
+        signal_ui(ui, name, signal, base);
+    }
+}
+
+fn signal_ui(ui: &mut egui::Ui, name: &str, signal: &mut Option<Signal>, base: f32) {
+    let selected = signal.as_ref().map_or("Constant", Signal::name);
+    ComboBox::from_label(name)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            let kinds = [
+                None,
+                Some(Signal::Sine {
+                    offset: base,
+                    amplitude: 1.0,
+                    frequency: 0.5,
+                    phase: 0.0,
+                }),
+                Some(Signal::Ramp {
+                    from: base,
+                    to: base + 1.0,
+                    start: 0.0,
+                    duration: 1.0,
+                }),
+                Some(Signal::Keyframes(vec![(0.0, base), (1.0, base)])),
+                Some(Signal::Expression(Expression::new(format!(
+                    "{} + sin(t)",
+                    base
+                )))),
+            ];
+            for kind in kinds {
+                let text = kind.as_ref().map_or("Constant", Signal::name);
+                if ui.selectable_label(selected == text, text).clicked() && selected != text {
+                    *signal = kind;
+                }
+            }
+        });
+
+    let signal = match signal {
+        Some(signal) => signal,
+        None => return,
+    };
+    ui.indent(name, |ui| match signal {
+        Signal::Sine {
+            offset,
+            amplitude,
+            frequency,
+            phase,
+        } => {
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(offset).prefix("offset: ").speed(0.01));
+                ui.add(DragValue::new(amplitude).prefix("amplitude: ").speed(0.01));
+            });
+            ui.horizontal(|ui| {
+                ui.add(
+                    DragValue::new(frequency)
+                        .prefix("frequency: ")
+                        .suffix(" Hz")
+                        .clamp_range(0.0..=f32::MAX)
+                        .speed(0.01),
+                );
+                ui.label("phase:");
+                ui.drag_angle(phase);
+            });
+        }
+        Signal::Ramp {
+            from,
+            to,
+            start,
+            duration,
+        } => {
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(from).prefix("from: ").speed(0.01));
+                ui.add(DragValue::new(to).prefix("to: ").speed(0.01));
+            });
+            ui.horizontal(|ui| {
+                ui.add(
+                    DragValue::new(start)
+                        .prefix("start: ")
+                        .suffix(" s")
+                        .speed(0.01),
+                );
+                ui.add(
+                    DragValue::new(duration)
+                        .prefix("over: ")
+                        .suffix(" s")
+                        .clamp_range(0.0..=f32::MAX)
+                        .speed(0.01),
+                );
+            });
+        }
+        Signal::Keyframes(keys) => {
+            let mut remove = None;
+            for (i, (t, v)) in keys.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(DragValue::new(t).suffix(" s").speed(0.01));
+                    ui.add(DragValue::new(v).speed(0.01));
+                    if ui.small_button("🗙").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                keys.remove(i);
+            }
+            if ui.small_button("Add key").clicked() {
+                let (t, v) = keys.last().copied().unwrap_or((0.0, base));
+                keys.push((t + 1.0, v));
+            }
+            // interpolation relies on the keys being in order
+            keys.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        Signal::Expression(expression) => {
+            ui.horizontal(|ui| {
+                ui.label("f(t) =");
+                let mut text = expression.text().to_owned();
+                if ui.text_edit_singleline(&mut text).changed() {
+                    expression.set_text(text);
+                }
+            });
+            if let Some(error) = expression.error() {
+                ui.colored_label(Color32::from_rgb(250, 135, 97), error);
+            }
+        }
+    });
+}
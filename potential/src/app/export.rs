@@ -0,0 +1,232 @@
+use std::io;
+
+use archie_egui::egui;
+use egui::{ComboBox, DragValue};
+
+use super::graph::{Graph, Source};
+use potential::{
+    graph::{linspace, GridData, Tikz},
+    scenario::FormulaText,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(super) enum Kind {
+    Svg,
+    Tikz,
+    Csv,
+    Npy,
+    Vtk,
+}
+
+impl Kind {
+    const ALL: [Kind; 5] = [Kind::Svg, Kind::Tikz, Kind::Csv, Kind::Npy, Kind::Vtk];
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Svg => "SVG",
+            Kind::Tikz => "TikZ",
+            Kind::Csv => "CSV",
+            Kind::Npy => "NumPy",
+            Kind::Vtk => "VTK",
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn extension(self) -> &'static str {
+        match self {
+            Kind::Svg => "svg",
+            Kind::Tikz => "tex",
+            Kind::Csv => "csv",
+            Kind::Npy => "npy",
+            Kind::Vtk => "vtk",
+        }
+    }
+
+    /// Sampled values rather than a picture.
+    fn is_data(self) -> bool {
+        matches!(self, Kind::Csv | Kind::Npy | Kind::Vtk)
+    }
+}
+
+/// The export window, saving the graph as a picture for slides and worksheets,
+/// or as sampled values for analysis.
+pub(super) struct Export {
+    pub kind: Kind,
+    pub contours: u32,
+    /// Samples along each axis of the data.
+    pub points: u32,
+    /// Keep the heatmap underneath, as an embedded image.
+    pub heatmap: bool,
+    pub tikz: Tikz,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+}
+
+impl Export {
+    pub fn new() -> Self {
+        Self {
+            kind: Kind::Svg,
+            contours: 10,
+            points: 64,
+            heatmap: true,
+            tikz: Tikz::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: "potential.svg".to_owned(),
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph: &Graph, source: &Source, formula: &FormulaText) {
+        let mut kind = self.kind;
+        ComboBox::from_label("Format")
+            .selected_text(kind.name())
+            .show_ui(ui, |ui| {
+                for k in Kind::ALL {
+                    ui.selectable_value(&mut kind, k, k.name());
+                }
+            });
+        if kind != self.kind {
+            self.set_kind(kind);
+        }
+        ui.horizontal(|ui| match self.kind.is_data() {
+            true => {
+                ui.label("Points");
+                ui.add(DragValue::new(&mut self.points).clamp_range(2..=1024));
+            }
+            false => {
+                ui.label("Contours");
+                ui.add(DragValue::new(&mut self.contours).clamp_range(0..=64));
+            }
+        });
+        match self.kind {
+            Kind::Svg => {
+                ui.checkbox(&mut self.heatmap, "Embed heatmap");
+            }
+            Kind::Tikz => tikz_ui(ui, &mut self.tikz),
+            _ => {
+                ui.weak("In volts and volts per metre over the figure.");
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut path = self.path.clone();
+        ui.horizontal(|ui| {
+            // anything but NumPy is text
+            if self.kind != Kind::Npy && ui.button(format!("Copy {}", self.kind.name())).clicked() {
+                if let Ok((bytes, _)) = self.contents(graph, source) {
+                    ui.output().copied_text = String::from_utf8_lossy(&bytes).into_owned();
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.text_edit_singleline(&mut path);
+                if ui.button(format!("Save {}", self.kind.name())).clicked() {
+                    if let Err(e) = self.save(&path, graph, source, formula) {
+                        log::error!("failed to save {}: {}", path, e);
+                    }
+                }
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.path = path;
+        }
+    }
+
+    /// The exported file, along with the data it came from if it's sampled.
+    fn contents(&self, graph: &Graph, source: &Source) -> io::Result<(Vec<u8>, Option<GridData>)> {
+        let figure = &graph.figure;
+        let mut scene = graph.scene();
+        scene.contours = self.contours;
+        let data = match self.kind {
+            Kind::Svg => {
+                if !self.heatmap {
+                    scene.heatmap = None;
+                }
+                let [width, height] = graph.size();
+                let svg = scene.svg(figure, source, width, height);
+                return Ok((svg.into_bytes(), None));
+            }
+            Kind::Tikz => {
+                let tex = scene.tikz(figure, source, &self.tikz);
+                return Ok((tex.into_bytes(), None));
+            }
+            _ => {
+                let xs = linspace(figure.x_min, figure.x_max, self.points);
+                let ys = linspace(figure.y_min, figure.y_max, self.points);
+                GridData::sample(xs, ys, source)
+            }
+        };
+        let mut bytes = Vec::new();
+        match self.kind {
+            Kind::Csv => data.write_csv(&mut bytes)?,
+            Kind::Npy => data.write_npy(&mut bytes)?,
+            _ => data.write_vtk(&mut bytes)?,
+        }
+        Ok((bytes, Some(data)))
+    }
+
+    /// Writes the export to `path`,
+    /// with what the numbers mean in a JSON file alongside any data.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(
+        &self,
+        path: &str,
+        graph: &Graph,
+        source: &Source,
+        formula: &FormulaText,
+    ) -> io::Result<()> {
+        let (bytes, data) = self.contents(graph, source)?;
+        std::fs::write(path, bytes)?;
+        if let Some(data) = data {
+            let stem = path.rsplit_once('.').map_or(path, |(stem, _)| stem);
+            let file = std::fs::File::create(format!("{}.json", stem))?;
+            let formula = match source {
+                Source::Particles(_) => None,
+                Source::Formula(_) => Some(formula),
+            };
+            let particles = graph.slice.world(source.particles());
+            data.write_metadata(&particles, &graph.slice, formula, io::BufWriter::new(file))?;
+        }
+        Ok(())
+    }
+
+    /// Switches format, keeping the extension of the path in line with it.
+    fn set_kind(&mut self, kind: Kind) {
+        self.kind = kind;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let stem = self
+                .path
+                .rsplit_once('.')
+                .map_or(&*self.path, |(stem, _)| stem);
+            self.path = format!("{}.{}", stem, kind.extension());
+        }
+    }
+}
+
+fn tikz_ui(ui: &mut egui::Ui, tikz: &mut Tikz) {
+    ui.horizontal(|ui| {
+        ui.label("Width");
+        ui.add(
+            DragValue::new(&mut tikz.width)
+                .clamp_range(1.0..=50.0)
+                .suffix(" cm"),
+        );
+        ui.checkbox(&mut tikz.standalone, "Standalone");
+    });
+    ui.collapsing("Styles", |ui| {
+        for (name, style) in [
+            ("Contour", &mut tikz.contour),
+            ("Field line", &mut tikz.field_line),
+            ("Positive", &mut tikz.positive),
+            ("Negative", &mut tikz.negative),
+            ("Axis", &mut tikz.axis),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(name);
+                ui.text_edit_singleline(style);
+            });
+        }
+    });
+}
@@ -0,0 +1,124 @@
+use archie_egui::egui;
+use egui::Color32;
+
+use potential::scenario::{Scenario, ScenarioError};
+
+/// Where the web build lives, for links copied from the native app.
+#[cfg(not(target_arch = "wasm32"))]
+const SITE: &str = "https://joe-loach.github.io/potential/";
+
+/// The file menu, for opening and saving scenarios.
+pub(super) struct FileMenu {
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    error: Option<String>,
+}
+
+impl FileMenu {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            path: "scenario.json".to_owned(),
+            error: None,
+        }
+    }
+
+    /// Returns a scenario if one was opened.
+    pub fn ui(&mut self, ui: &mut egui::Ui, current: &Scenario) -> Option<Scenario> {
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut opened = None;
+        ui.menu_button("File", |ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.text_edit_singleline(&mut self.path);
+                ui.horizontal(|ui| {
+                    if ui.button("Open").clicked() {
+                        match self.open() {
+                            Ok(scenario) => {
+                                opened = Some(scenario);
+                                self.error = None;
+                                ui.close_menu();
+                            }
+                            Err(e) => self.fail("open", e),
+                        }
+                    }
+                    if ui.button("Save").clicked() {
+                        match std::fs::write(&self.path, current.to_json()) {
+                            Ok(()) => {
+                                self.error = None;
+                                ui.close_menu();
+                            }
+                            Err(e) => self.fail("save", e.into()),
+                        }
+                    }
+                });
+            }
+            if ui.button("Copy link").clicked() {
+                match link(current) {
+                    Ok(link) => {
+                        ui.output().copied_text = link;
+                        self.error = None;
+                        ui.close_menu();
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+            if ui.button("Copy as JSON").clicked() {
+                ui.output().copied_text = current.to_json();
+                ui.close_menu();
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(Color32::from_rgb(250, 135, 97), error);
+            }
+        });
+        opened
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open(&self) -> anyhow::Result<Scenario> {
+        let text = std::fs::read_to_string(&self.path)?;
+        Ok(text.parse()?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fail(&mut self, action: &str, e: anyhow::Error) {
+        log::error!("failed to {} {}: {}", action, self.path, e);
+        self.error = Some(e.to_string());
+    }
+}
+
+/// A link to the web build that opens `scenario`.
+#[cfg(not(target_arch = "wasm32"))]
+fn link(scenario: &Scenario) -> Result<String, ScenarioError> {
+    Ok(format!("{}#{}", SITE, scenario.to_link()?))
+}
+
+/// A link to this page that opens `scenario`,
+/// which also goes in the address bar.
+#[cfg(target_arch = "wasm32")]
+fn link(scenario: &Scenario) -> Result<String, ScenarioError> {
+    let code = scenario.to_link()?;
+    let page = web_sys::window()
+        .map(|w| w.location())
+        .and_then(|location| {
+            let _ = location.set_hash(&code);
+            let origin = location.origin().ok()?;
+            let path = location.pathname().ok()?;
+            Some(origin + &path)
+        });
+    Ok(format!("{}#{}", page.unwrap_or_default(), code))
+}
+
+/// The scenario in the fragment of the page's URL, if there is one.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn linked() -> Option<Scenario> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let code = hash.strip_prefix('#').filter(|code| !code.is_empty())?;
+    match Scenario::from_link(code) {
+        Ok(scenario) => Some(scenario),
+        Err(e) => {
+            log::error!("failed to open the linked scenario: {}", e);
+            None
+        }
+    }
+}
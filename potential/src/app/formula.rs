@@ -0,0 +1,97 @@
+use archie_egui::egui;
+use egui::{Color32, DragValue, RichText};
+
+use potential::{expr::ParseError, scenario::FormulaText, Formula};
+
+/// Types in the potential for [`Geometry::Formula`](potential::Geometry::Formula).
+pub(super) struct FormulaEditor {
+    text: String,
+    /// The last formula that parsed.
+    formula: Formula,
+    error: Option<ParseError>,
+}
+
+impl FormulaEditor {
+    pub fn new() -> Self {
+        let text = "x^2 - y^2".to_owned();
+        Self {
+            formula: text.parse().unwrap(),
+            text,
+            error: None,
+        }
+    }
+
+    /// The formula at time `t`.
+    pub fn at(&self, t: f32) -> Formula {
+        let mut formula = self.formula.clone();
+        formula.time = t;
+        formula
+    }
+
+    pub fn uses_time(&self) -> bool {
+        self.formula.uses_time()
+    }
+
+    /// The formula as it was typed, to be saved in a scenario.
+    pub fn saved(&self) -> FormulaText {
+        FormulaText {
+            text: self.text.clone(),
+            params: self.formula.params.clone(),
+        }
+    }
+
+    pub fn load(&mut self, saved: &FormulaText) {
+        self.text = saved.text.clone();
+        match saved.formula() {
+            Ok(formula) => {
+                self.formula = formula;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Formula");
+        ui.horizontal(|ui| {
+            ui.monospace("V(x, y) =");
+            let response = ui.add(egui::TextEdit::singleline(&mut self.text).code_editor());
+            if response.changed() {
+                match self.text.parse::<Formula>() {
+                    Ok(mut formula) => {
+                        formula.keep_params(&self.formula);
+                        self.formula = formula;
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+        });
+        if let Some(error) = &self.error {
+            let color = Color32::from_rgb(250, 135, 97);
+            if let Some(at) = error.at() {
+                // point at the problem
+                let marker = format!("{}^", " ".repeat(self.text[..at].chars().count()));
+                ui.monospace(&self.text);
+                ui.label(RichText::new(marker).monospace().color(color));
+            }
+            ui.colored_label(color, error.to_string());
+        }
+        ui.small("Use x, y, t, COULOMB and GRAVITATIONAL; any other name is a parameter.");
+
+        ui.separator();
+        let [ex, ey] = self.formula.field();
+        ui.monospace(format!("E_x = {}", ex));
+        ui.monospace(format!("E_y = {}", ey));
+
+        if !self.formula.params.is_empty() {
+            ui.separator();
+            for (name, value) in &mut self.formula.params {
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("{} =", name));
+                    ui.add(DragValue::new(value).speed(0.01));
+                });
+            }
+        }
+    }
+}
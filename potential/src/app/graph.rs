@@ -0,0 +1,638 @@
+use archie_egui::egui;
+use egui::{
+    plot::{Legend, Line, LineStyle, Plot, VLine, Value, Values},
+    Color32, ComboBox, CursorIcon, DragValue, Key, Pos2, Rect, Shape, Stroke,
+};
+use glam::Vec2;
+
+use potential::{
+    animation::Track,
+    edit::{grab, hit, Edit, Grab},
+    graph::{
+        Colormap, Figure, Heatmap, Image, Lic, LineCut, ProfilePoint, Quantity, Quiver, Range,
+        Samples, Scale, Scaling, Scene, Streamlines, Subject, Viewport,
+    },
+    scenario::View,
+    Field, Formula, Geometry, Particle, Sheets, Slice,
+};
+
+/// Where the field in the graph comes from.
+#[derive(Clone, PartialEq)]
+pub(super) enum Source {
+    /// Relative to the slice.
+    Particles(Vec<Particle>),
+    Formula(Formula),
+}
+
+impl Source {
+    /// The particles to mark on the graph, if any.
+    pub fn particles(&self) -> &[Particle] {
+        match self {
+            Source::Particles(particles) => particles,
+            Source::Formula(_) => &[],
+        }
+    }
+}
+
+impl Subject for Source {
+    fn particles(&self) -> &[Particle] {
+        Source::particles(self)
+    }
+}
+
+impl<T> Field<T> for Source
+where
+    for<'a> &'a [Particle]: Field<T>,
+    Formula: Field<T>,
+{
+    fn at(&self, pos: Vec2) -> T {
+        match self {
+            Source::Particles(particles) => particles.as_slice().at(pos),
+            Source::Formula(formula) => formula.at(pos),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(super) enum Background {
+    Heatmap,
+    Lic,
+}
+
+/// How close the mouse has to be to a particle to pick it up, in points.
+const REACH: f32 = 4.0;
+
+/// The inputs of the last expensive background that was drawn.
+#[derive(PartialEq)]
+struct Drawn {
+    source: Source,
+    figure: Figure,
+    heatmap: Heatmap,
+    lic: Lic,
+    size: [u32; 2],
+}
+
+/// Everything drawn in the graph tab.
+pub(super) struct Graph {
+    pub figure: Figure,
+    pub slice: Slice,
+    pub heatmap: Heatmap,
+    pub quantity: Quantity,
+    pub background: Background,
+    pub lic: Lic,
+    pub quiver: Quiver,
+    pub arrows: bool,
+    pub streamlines: Streamlines,
+    pub field_lines: bool,
+    /// Keep circles round, whatever shape the graph is.
+    pub lock_aspect: bool,
+    texture: Option<egui::TextureHandle>,
+    drawn: Option<Drawn>,
+    lines: Vec<Vec<Vec2>>,
+    lines_drawn: Option<(Source, Figure, Streamlines)>,
+    grab: Option<Grab>,
+    /// How big the graph was last drawn, in points.
+    size: egui::Vec2,
+}
+
+impl Graph {
+    pub fn new(figure: Figure) -> Self {
+        Self {
+            figure,
+            slice: Slice::default(),
+            heatmap: Heatmap::default(),
+            quantity: Quantity::Voltage,
+            background: Background::Heatmap,
+            lic: Lic::default(),
+            quiver: Quiver::default(),
+            arrows: false,
+            streamlines: Streamlines::default(),
+            field_lines: false,
+            lock_aspect: false,
+            texture: None,
+            drawn: None,
+            lines: Vec::new(),
+            lines_drawn: None,
+            grab: None,
+            size: egui::vec2(800.0, 600.0),
+        }
+    }
+
+    /// What's on screen, to be drawn again outside of the app.
+    pub fn scene(&self) -> Scene {
+        Scene {
+            quantity: self.quantity,
+            heatmap: (self.background == Background::Heatmap).then_some(self.heatmap),
+            streamlines: self.field_lines.then_some(self.streamlines),
+            ..Default::default()
+        }
+    }
+
+    /// How the graph is drawn, to be saved in a scenario.
+    pub fn view(&self) -> View {
+        View {
+            quantity: self.quantity,
+            colormap: self.heatmap.colormap,
+            scale: self.heatmap.scale,
+            lic: self.background == Background::Lic,
+            arrows: self.arrows,
+            field_lines: self.field_lines,
+            slice: self.slice,
+        }
+    }
+
+    pub fn set_view(&mut self, view: &View) {
+        self.quantity = view.quantity;
+        self.heatmap.colormap = view.colormap;
+        self.heatmap.scale = view.scale;
+        self.background = match view.lic {
+            true => Background::Lic,
+            false => Background::Heatmap,
+        };
+        self.arrows = view.arrows;
+        self.field_lines = view.field_lines;
+        self.slice = view.slice;
+    }
+
+    /// Frames the particles, leaving a little room around them.
+    pub fn zoom_to_fit(&mut self, particles: &[Particle]) {
+        let mut view = Viewport::new(self.figure, Vec2::new(self.size.x, self.size.y));
+        view.fit(particles, 0.1);
+        if self.lock_aspect {
+            view.lock_aspect();
+        }
+        self.figure = view.figure;
+    }
+
+    /// The size of the graph on screen, rounded to whole points.
+    pub fn size(&self) -> [u32; 2] {
+        [self.size.x.max(1.0) as u32, self.size.y.max(1.0) as u32]
+    }
+
+    /// Draws the graph, handing back any change made to the particles on it.
+    ///
+    /// Clicking places a particle, and they can be dragged around by their body
+    /// or resized by their rim, unless that's animated by their track.
+    /// Delete removes the one under the mouse.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        source: &Source,
+        tracks: &[Track],
+    ) -> (egui::Response, Option<Edit>) {
+        let (rect, response) =
+            ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+        self.size = rect.size();
+        let edit = self.edit_ui(ui, &response, source, tracks);
+
+        // scroll to zoom in on the cursor, drag anywhere else to pan
+        let mut view = Viewport::new(self.figure, Vec2::new(rect.width(), rect.height()));
+        if response.hovered() {
+            let input = ui.input();
+            let factor = input.zoom_delta() * (input.scroll_delta.y / 200.0).exp();
+            if let Some(pos) = input.pointer.hover_pos() {
+                view.zoom_at(Vec2::new(pos.x - rect.min.x, pos.y - rect.min.y), factor);
+            }
+        }
+        if self.grab.is_none() && response.dragged_by(egui::PointerButton::Primary) {
+            let delta = response.drag_delta();
+            view.pan(Vec2::new(delta.x, delta.y));
+        }
+        if self.lock_aspect {
+            view.lock_aspect();
+        }
+        self.figure = view.figure;
+        if let Some(image) = self.background(rect, source) {
+            let image = egui::ColorImage::from_rgba_unmultiplied(
+                [image.width as usize, image.height as usize],
+                image.as_bytes(),
+            );
+            match &mut self.texture {
+                Some(texture) => texture.set(image),
+                None => self.texture = Some(ui.ctx().load_texture("Background", image)),
+            }
+        }
+        if let Some(texture) = &self.texture {
+            egui::Image::new(texture, rect.size()).paint_at(ui, rect);
+        }
+
+        let painter = ui.painter_at(rect);
+        let to_screen = |pos| to_screen(&self.figure, rect, pos);
+
+        if self.field_lines {
+            let drawn = (source.clone(), self.figure, self.streamlines);
+            if self.lines_drawn.as_ref() != Some(&drawn) {
+                self.lines = self.streamlines.place(&self.figure, source);
+                self.lines_drawn = Some(drawn);
+            }
+            for line in &self.lines {
+                let points = line.iter().map(|&p| to_screen(p)).collect();
+                painter.add(Shape::line(points, Stroke::new(1.0, Color32::WHITE)));
+            }
+        }
+
+        for p in source.particles() {
+            let centre = to_screen(p.pos.truncate());
+            let scale = rect.width() / (self.figure.x_max - self.figure.x_min);
+            let color = if p.value >= 0.0 {
+                Color32::from_rgb(214, 96, 77)
+            } else {
+                Color32::from_rgb(67, 147, 195)
+            };
+            // only the part of the sphere cut by the slice is drawn in full
+            let z = p.pos.z.abs();
+            if z < p.radius {
+                let r = (p.radius * p.radius - z * z).sqrt();
+                painter.circle_stroke(centre, r * scale, Stroke::new(2.0, color));
+            } else {
+                painter.circle_filled(centre, 3.0, color.linear_multiply(0.5));
+            }
+        }
+
+        if self.arrows {
+            for arrow in self.quiver.arrows(&self.figure, source) {
+                let color = match self.quiver.scaling {
+                    Scaling::Normalised => {
+                        let [r, g, b, _] = Colormap::Magma.at(arrow.weight);
+                        Color32::from_rgb(r, g, b)
+                    }
+                    _ => Color32::WHITE,
+                };
+                for [a, b] in arrow.segments() {
+                    painter.line_segment([to_screen(a), to_screen(b)], Stroke::new(1.0, color));
+                }
+            }
+        }
+        (response, edit)
+    }
+
+    fn edit_ui(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        source: &Source,
+        tracks: &[Track],
+    ) -> Option<Edit> {
+        let rect = response.rect;
+        let shown = source.particles();
+        // the size of a point in the figure
+        let point = (self.figure.x_max - self.figure.x_min) / rect.width();
+        let to_world = |pos| from_screen(&self.figure, rect, pos);
+        let mut edit = None;
+
+        if response.drag_started() {
+            self.grab = response
+                .interact_pointer_pos()
+                .and_then(|pos| grab(shown, tracks, to_world(pos), REACH * point));
+        }
+        if !response.dragged() {
+            self.grab = None;
+        }
+        if let (Some(grab), Some(pos)) = (self.grab, response.interact_pointer_pos()) {
+            let pos = to_world(pos);
+            edit = Some(match grab {
+                Grab::Body { index, offset } => Edit::Move {
+                    index,
+                    to: pos - offset,
+                },
+                Grab::Rim { index } => Edit::Resize {
+                    index,
+                    radius: (pos.extend(0.0) - shown[index].pos).length().max(point),
+                },
+            });
+        }
+
+        let mouse = response.hover_pos().map(to_world);
+        let hovered = mouse.and_then(|pos| hit(shown, pos, REACH * point).map(|(index, _)| index));
+        let grabbable = mouse.and_then(|pos| grab(shown, tracks, pos, REACH * point));
+        let cursor = match (self.grab, grabbable) {
+            (Some(Grab::Rim { .. }), _) | (None, Some(Grab::Rim { .. })) => CursorIcon::ResizeNwSe,
+            (Some(_), _) => CursorIcon::Grabbing,
+            (None, Some(_)) => CursorIcon::Grab,
+            // held where it is by its track
+            (None, None) if hovered.is_some() => CursorIcon::NotAllowed,
+            (None, None) => CursorIcon::Crosshair,
+        };
+        // there's nothing to place from a formula
+        if matches!(source, Source::Particles(_)) || hovered.is_some() {
+            ui.output().cursor_icon = cursor;
+        }
+
+        match hovered {
+            Some(index) => {
+                let typing = ui.memory().focus().is_some();
+                let input = ui.input();
+                if !typing && (input.key_pressed(Key::Delete) || input.key_pressed(Key::Backspace))
+                {
+                    edit = Some(Edit::Delete(index));
+                }
+            }
+            None if response.clicked() && matches!(source, Source::Particles(_)) => {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    edit = Some(Edit::Place {
+                        pos: to_world(pos),
+                        negative: ui.input().modifiers.shift,
+                    });
+                }
+            }
+            None => {}
+        }
+        edit
+    }
+
+    /// Draws a line cut over the graph.
+    pub fn cut_ui(&self, ui: &egui::Ui, rect: Rect, cut: &LineCut) {
+        let to_screen = |pos| to_screen(&self.figure, rect, pos);
+        let stroke = Stroke::new(2.0, Color32::from_rgb(90, 170, 255));
+        let painter = ui.painter_at(rect);
+        painter.line_segment([to_screen(cut.from), to_screen(cut.to)], stroke);
+        painter.circle_filled(to_screen(cut.from), 3.0, stroke.color);
+    }
+
+    /// A new background image, if it needs redrawing.
+    fn background(&mut self, rect: Rect, source: &Source) -> Option<Image> {
+        // sample at a lower resolution than the screen and let the texture stretch
+        let downscale = match self.background {
+            Background::Heatmap => 4.0,
+            Background::Lic => self.lic.downscale as f32,
+        };
+        let width = (rect.width() / downscale).max(1.0) as u32;
+        let height = (rect.height() / downscale).max(1.0) as u32;
+
+        match self.background {
+            Background::Heatmap => {
+                self.drawn = None;
+                let quantity = self.quantity;
+                let samples = Samples::new(&self.figure, width, height, |pos| {
+                    quantity.eval(source, pos)
+                });
+                Some(self.heatmap.colorize(&samples))
+            }
+            Background::Lic => {
+                // far too slow to redraw every frame
+                let drawn = Drawn {
+                    source: source.clone(),
+                    figure: self.figure,
+                    heatmap: self.heatmap,
+                    lic: self.lic,
+                    size: [width, height],
+                };
+                if self.drawn.as_ref() == Some(&drawn) {
+                    return None;
+                }
+                self.drawn = Some(drawn);
+                Some(
+                    self.lic
+                        .render(&self.figure, width, height, source, &self.heatmap),
+                )
+            }
+        }
+    }
+
+    /// Potential and field across the figure, for a scene of sheets.
+    pub fn sheets_ui(&self, ui: &mut egui::Ui, particles: &[Particle]) {
+        let cut = LineCut::horizontal(&self.figure, 0.0, 1024);
+        let points = cut.sample(&Sheets(particles));
+        let line = |name: &str, color: Color32, f: fn(&ProfilePoint) -> f32| {
+            let values = points.iter().map(|p| Value::new(p.pos.x, f(p)));
+            Line::new(Values::from_values_iter(values))
+                .name(name)
+                .color(color)
+        };
+        Plot::new("Sheets Plot")
+            .legend(Legend::default())
+            .include_x(self.figure.x_min)
+            .include_x(self.figure.x_max)
+            .show(ui, |plot| {
+                plot.line(line("V(x)", Color32::from_rgb(90, 170, 255), |p| p.voltage));
+                plot.line(line("E(x)", Color32::from_rgb(250, 135, 97), |p| {
+                    p.component
+                }));
+                for p in particles {
+                    plot.vline(
+                        VLine::new(p.pos.x)
+                            .color(Color32::from_gray(140))
+                            .style(LineStyle::dashed_dense())
+                            .name("Sheet"),
+                    );
+                }
+            });
+    }
+
+    pub fn settings_ui(&mut self, ui: &mut egui::Ui, geometry: Geometry) {
+        figure_ui(ui, &mut self.figure, &mut self.lock_aspect);
+        if geometry == Geometry::Sheets {
+            return;
+        }
+        ui.separator();
+        if geometry == Geometry::Points {
+            slice_ui(ui, &mut self.slice);
+            ui.separator();
+        }
+        background_ui(ui, &mut self.background, &mut self.lic);
+        heatmap_ui(ui, &mut self.heatmap, &mut self.quantity);
+        ui.separator();
+        quiver_ui(ui, &mut self.quiver, &mut self.arrows);
+        ui.separator();
+        streamlines_ui(ui, &mut self.streamlines, &mut self.field_lines);
+    }
+}
+
+fn viewport(figure: &Figure, rect: Rect) -> Viewport {
+    Viewport::new(*figure, Vec2::new(rect.width(), rect.height()))
+}
+
+/// Maps a position in the figure onto the screen.
+pub(super) fn to_screen(figure: &Figure, rect: Rect, pos: Vec2) -> Pos2 {
+    let p = viewport(figure, rect).to_screen(pos);
+    rect.min + egui::vec2(p.x, p.y)
+}
+
+/// Maps a point on the screen back into the figure.
+pub(super) fn from_screen(figure: &Figure, rect: Rect, pos: Pos2) -> Vec2 {
+    let p = pos - rect.min;
+    viewport(figure, rect).to_world(Vec2::new(p.x, p.y))
+}
+
+fn figure_ui(ui: &mut egui::Ui, f: &mut Figure, lock_aspect: &mut bool) {
+    let Figure {
+        x_min,
+        x_max,
+        y_min,
+        y_max,
+        ..
+    } = f;
+
+    ui.heading("Figure");
+    ui.horizontal(|ui| {
+        ui.add(
+            DragValue::new(x_min)
+                .clamp_range(f32::NEG_INFINITY..=*x_max)
+                .fixed_decimals(2),
+        );
+        ui.monospace("≤ X ≤");
+        ui.add(
+            DragValue::new(x_max)
+                .clamp_range(*x_min..=f32::INFINITY)
+                .fixed_decimals(2),
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.add(
+            DragValue::new(y_min)
+                .clamp_range(f32::NEG_INFINITY..=*y_max)
+                .fixed_decimals(2),
+        );
+        ui.monospace("≤ Y ≤");
+        ui.add(
+            DragValue::new(y_max)
+                .clamp_range(*y_min..=f32::INFINITY)
+                .fixed_decimals(2),
+        );
+    });
+    let (width, height) = f.extent();
+    ui.weak(format!("{} × {}", width, height));
+    ui.checkbox(lock_aspect, "Lock aspect ratio");
+}
+
+fn slice_ui(ui: &mut egui::Ui, slice: &mut Slice) {
+    ui.heading("Slice");
+    ui.horizontal(|ui| {
+        ui.label("Offset");
+        ui.add(DragValue::new(&mut slice.offset).speed(0.01).suffix(" m"));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Yaw   ");
+        ui.drag_angle(&mut slice.yaw);
+        ui.label("Pitch");
+        ui.drag_angle(&mut slice.pitch);
+    });
+    if ui.button("Reset").clicked() {
+        *slice = Slice::default();
+    }
+}
+
+fn background_ui(ui: &mut egui::Ui, background: &mut Background, lic: &mut Lic) {
+    ui.heading("Background");
+    ui.horizontal(|ui| {
+        ui.radio_value(background, Background::Heatmap, "Heatmap");
+        ui.radio_value(background, Background::Lic, "Field texture");
+    });
+    if *background == Background::Lic {
+        ui.horizontal(|ui| {
+            ui.label("Kernel");
+            ui.add(
+                DragValue::new(&mut lic.kernel)
+                    .clamp_range(1..=64)
+                    .suffix(" px"),
+            );
+            ui.label("Seed");
+            ui.add(DragValue::new(&mut lic.seed));
+            ui.label("Resolution");
+            ui.add(
+                DragValue::new(&mut lic.downscale)
+                    .clamp_range(1..=8)
+                    .prefix("1/"),
+            );
+        });
+    }
+}
+
+fn heatmap_ui(ui: &mut egui::Ui, heatmap: &mut Heatmap, quantity: &mut Quantity) {
+    ui.heading("Heatmap");
+    ComboBox::from_label("Quantity")
+        .selected_text(quantity.name())
+        .show_ui(ui, |ui| {
+            for q in [Quantity::Voltage, Quantity::Field] {
+                ui.selectable_value(quantity, q, q.name());
+            }
+        });
+    ComboBox::from_label("Colormap")
+        .selected_text(heatmap.colormap.name())
+        .show_ui(ui, |ui| {
+            for c in Colormap::ALL {
+                ui.selectable_value(&mut heatmap.colormap, c, c.name());
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut heatmap.scale, Scale::Linear, "Linear");
+        ui.radio_value(&mut heatmap.scale, Scale::Log, "Log");
+        if ui
+            .radio(matches!(heatmap.scale, Scale::Symlog { .. }), "Symlog")
+            .clicked()
+        {
+            heatmap.scale = Scale::Symlog { threshold: 1.0 };
+        }
+    });
+    if let Scale::Symlog { threshold } = &mut heatmap.scale {
+        ui.horizontal(|ui| {
+            ui.label("Threshold");
+            ui.add(
+                DragValue::new(threshold)
+                    .clamp_range(f32::EPSILON..=f32::INFINITY)
+                    .speed(0.01),
+            );
+        });
+    }
+    if let Range::Percentile { low, high } = &mut heatmap.range {
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(low).clamp_range(0.0..=*high).suffix(" %"));
+            ui.monospace("≤ P ≤");
+            ui.add(DragValue::new(high).clamp_range(*low..=100.0).suffix(" %"));
+        });
+    }
+}
+
+fn quiver_ui(ui: &mut egui::Ui, quiver: &mut Quiver, show: &mut bool) {
+    ui.horizontal(|ui| {
+        ui.heading("Arrows");
+        ui.checkbox(show, "");
+    });
+    if !*show {
+        return;
+    }
+    ComboBox::from_label("Length")
+        .selected_text(quiver.scaling.name())
+        .show_ui(ui, |ui| {
+            for s in Scaling::ALL {
+                ui.selectable_value(&mut quiver.scaling, s, s.name());
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.label("Spacing  ");
+        ui.add(
+            DragValue::new(&mut quiver.spacing)
+                .clamp_range(0.01..=f32::INFINITY)
+                .speed(0.01)
+                .suffix(" m"),
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.label("Clearance");
+        ui.add(
+            DragValue::new(&mut quiver.clearance)
+                .clamp_range(0.0..=f32::INFINITY)
+                .speed(0.01)
+                .suffix(" m"),
+        );
+    });
+}
+
+fn streamlines_ui(ui: &mut egui::Ui, streamlines: &mut Streamlines, show: &mut bool) {
+    ui.horizontal(|ui| {
+        ui.heading("Field lines");
+        ui.checkbox(show, "");
+    });
+    if !*show {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label("Separation");
+        ui.add(
+            DragValue::new(&mut streamlines.separation)
+                .clamp_range(0.01..=f32::INFINITY)
+                .speed(0.01)
+                .suffix(" m"),
+        );
+    });
+}
@@ -0,0 +1,73 @@
+use archie_egui::egui;
+use egui::{Button, RichText, ScrollArea};
+
+use potential::history::{Document, History};
+
+/// Undo and redo, returning whether the history window was asked for.
+pub(super) fn edit_menu(ui: &mut egui::Ui, history: &mut History, doc: &mut Document) -> bool {
+    let mut show = false;
+    ui.menu_button("Edit", |ui| {
+        if ui
+            .add_enabled(history.can_undo(), Button::new("Undo (Ctrl+Z)"))
+            .clicked()
+        {
+            history.undo(doc);
+            ui.close_menu();
+        }
+        if ui
+            .add_enabled(history.can_redo(), Button::new("Redo (Ctrl+Y)"))
+            .clicked()
+        {
+            history.redo(doc);
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("History").clicked() {
+            show = true;
+            ui.close_menu();
+        }
+    });
+    show
+}
+
+/// Every step, oldest at the top.
+/// Clicking on one goes back, or forward, to just after it.
+pub(super) fn history_ui(ui: &mut egui::Ui, history: &mut History, doc: &mut Document) {
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(history.can_undo(), Button::new("Undo"))
+            .clicked()
+        {
+            history.undo(doc);
+        }
+        if ui
+            .add_enabled(history.can_redo(), Button::new("Redo"))
+            .clicked()
+        {
+            history.redo(doc);
+        }
+    });
+    ui.separator();
+    let done = history.done().len();
+    let mut target = None;
+    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        if ui.selectable_label(done == 0, "Start").clicked() {
+            target = Some(0);
+        }
+        for (i, command) in history.done().iter().enumerate() {
+            if ui.selectable_label(i + 1 == done, command.name()).clicked() {
+                target = Some(i + 1);
+            }
+        }
+        // undone steps are greyed out, next to be redone first
+        for (i, command) in history.undone().iter().rev().enumerate() {
+            let name = RichText::new(command.name()).weak();
+            if ui.selectable_label(false, name).clicked() {
+                target = Some(done + i + 1);
+            }
+        }
+    });
+    if let Some(target) = target {
+        history.go_to(target, doc);
+    }
+}
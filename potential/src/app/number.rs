@@ -0,0 +1,93 @@
+use archie_egui::egui;
+use egui::{Button, Color32, CursorIcon, Sense, TextEdit};
+
+use potential::scientific::{Exponent, Format, Notation, Sci};
+
+/// How numbers are shown in text boxes.
+const FORMAT: Format = Format {
+    sig_figs: 4,
+    notation: Notation::Si,
+    exponent: Exponent::E,
+};
+
+/// `value` followed by its unit, with an SI prefix such as `2.500 µC`.
+fn with_unit(value: f32, unit: &str) -> String {
+    let text = Sci(value).format(FORMAT).to_string();
+    match text.contains(' ') {
+        // the prefix is already spaced out
+        true => format!("{}{}", text, unit),
+        false => format!("{} {}", text, unit),
+    }
+}
+
+/// A text box for a number with a unit, in place of a `DragValue`
+/// so that tiny and huge values stay readable.
+///
+/// Anything [`Sci`] can read is accepted, with or without the unit after it.
+/// The value is updated as soon as the text makes sense.
+/// The handle beside it drags the value along like a `DragValue` would,
+/// a hundredth of its size for every point.
+pub(super) fn number_edit(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    value: &mut f32,
+    unit: &str,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let focused = ui.memory().has_focus(id);
+    // keep what's being typed, rather than reformatting it under the cursor
+    let mut text = match focused {
+        true => ui.data().get_temp::<String>(id),
+        false => None,
+    }
+    .unwrap_or_else(|| with_unit(*value, unit));
+
+    let parsed = parse(&text, unit);
+    let color = parsed.is_none().then(|| Color32::from_rgb(250, 135, 97));
+    let response = ui.add(
+        TextEdit::singleline(&mut text)
+            .id(id)
+            .desired_width(96.0)
+            .text_color_opt(color),
+    );
+    if response.changed() {
+        if let Some(x) = parse(&text, unit) {
+            *value = x;
+        }
+    }
+    if response.has_focus() {
+        ui.data().insert_temp(id, text);
+    } else {
+        ui.data().remove::<String>(id);
+    }
+
+    let mut handle = ui
+        .add(Button::new("↔").small().sense(Sense::drag()))
+        .on_hover_cursor(CursorIcon::ResizeHorizontal);
+    // fixed for the whole drag, so the value moves steadily
+    let step_id = id.with("step");
+    if handle.drag_started() {
+        let step = if *value == 0.0 {
+            0.01
+        } else {
+            0.01 * value.abs()
+        };
+        ui.data().insert_temp(step_id, step);
+    }
+    let delta = handle.drag_delta().x;
+    if handle.dragged() && delta != 0.0 {
+        let step = ui.data().get_temp::<f32>(step_id).unwrap_or(0.01);
+        *value += delta * step;
+        handle.mark_changed();
+    }
+    response | handle
+}
+
+/// The unit comes off the end before the prefix is read, so a lone unit
+/// is never taken for a prefix: with a unit of `m`, `3m` and `3 m` are both
+/// three metres, and `3 mm` is three millimetres.
+fn parse(text: &str, unit: &str) -> Option<f32> {
+    let text = text.trim();
+    let text = text.strip_suffix(unit).unwrap_or(text);
+    text.parse::<Sci>().ok().map(|x| x.0)
+}
@@ -0,0 +1,114 @@
+use archie_egui::egui;
+use egui::{
+    plot::{Legend, Line, LineStyle, Plot, VLine, Value, Values},
+    Color32, DragValue,
+};
+use glam::Vec2;
+
+use potential::graph::{write_csv, Figure, LineCut, ProfilePoint};
+
+use super::graph::Source;
+
+/// The line cut window, plotting the field along a segment.
+pub(super) struct Profile {
+    pub cut: LineCut,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+}
+
+impl Profile {
+    pub fn new(figure: &Figure) -> Self {
+        Self {
+            cut: LineCut::horizontal(figure, 0.0, 256),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: "profile.csv".to_owned(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, figure: &Figure, source: &Source) {
+        let cut = &mut self.cut;
+        ui.horizontal(|ui| {
+            ui.label("From");
+            point_ui(ui, &mut cut.from);
+        });
+        ui.horizontal(|ui| {
+            ui.label("To  ");
+            point_ui(ui, &mut cut.to);
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Along X").clicked() {
+                *cut =
+                    LineCut::horizontal(figure, 0.5 * (figure.y_min + figure.y_max), cut.samples);
+            }
+            if ui.button("Along Y").clicked() {
+                *cut = LineCut::vertical(figure, 0.5 * (figure.x_min + figure.x_max), cut.samples);
+            }
+            ui.label("Samples");
+            ui.add(DragValue::new(&mut cut.samples).clamp_range(2..=4096));
+        });
+
+        let points = cut.sample(source);
+        let line = |name: &str, color: Color32, f: fn(&ProfilePoint) -> f32| {
+            let values = points.iter().map(|p| Value::new(p.s, f(p)));
+            Line::new(Values::from_values_iter(values))
+                .name(name)
+                .color(color)
+        };
+        Plot::new("Line Cut Plot")
+            .width(400.0)
+            .height(250.0)
+            .legend(Legend::default())
+            .show(ui, |plot| {
+                plot.line(line("V", Color32::from_rgb(90, 170, 255), |p| p.voltage));
+                plot.line(line("|E|", Color32::from_rgb(250, 135, 97), |p| {
+                    p.magnitude
+                }));
+                plot.line(line("E∥", Color32::from_rgb(94, 201, 98), |p| {
+                    p.component
+                }));
+                for s in cut.boundaries(source.particles()) {
+                    plot.vline(
+                        VLine::new(s)
+                            .color(Color32::from_gray(140))
+                            .style(LineStyle::dashed_dense())
+                            .name("Surface"),
+                    );
+                }
+            });
+
+        ui.horizontal(|ui| {
+            if ui.button("Copy CSV").clicked() {
+                let mut csv = Vec::new();
+                if write_csv(&points, &mut csv).is_ok() {
+                    ui.output().copied_text = String::from_utf8_lossy(&csv).into_owned();
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.text_edit_singleline(&mut self.path);
+                if ui.button("Save CSV").clicked() {
+                    let result = std::fs::File::create(&self.path)
+                        .and_then(|file| write_csv(&points, std::io::BufWriter::new(file)));
+                    if let Err(e) = result {
+                        log::error!("failed to save {}: {}", self.path, e);
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn point_ui(ui: &mut egui::Ui, p: &mut Vec2) {
+    ui.add(
+        DragValue::new(&mut p.x)
+            .prefix("x: ")
+            .suffix(" m")
+            .speed(0.01),
+    );
+    ui.add(
+        DragValue::new(&mut p.y)
+            .prefix("y: ")
+            .suffix(" m")
+            .speed(0.01),
+    );
+}
@@ -0,0 +1,114 @@
+use archie_egui::egui;
+use egui::{
+    plot::{Legend, Line, LineStyle, MarkerShape, Plot, Points, VLine, Value, Values},
+    Color32, ComboBox, DragValue,
+};
+
+use potential::{
+    graph::{Radial, RadialPoint},
+    Particle,
+};
+
+/// The radial profile window, comparing samples against the analytic result.
+pub(super) struct RadialView {
+    /// The particle at the centre.
+    centre: usize,
+    r_max: f32,
+}
+
+impl RadialView {
+    pub fn new() -> Self {
+        Self {
+            centre: 0,
+            r_max: 5.0,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, particles: &[Particle]) {
+        if particles.is_empty() {
+            ui.label("Add a particle to see its profile");
+            return;
+        }
+        self.centre = self.centre.min(particles.len() - 1);
+        ui.horizontal(|ui| {
+            ComboBox::from_label("Centre")
+                .selected_text(format!("Particle {}", self.centre))
+                .show_ui(ui, |ui| {
+                    for i in 0..particles.len() {
+                        ui.selectable_value(&mut self.centre, i, format!("Particle {}", i));
+                    }
+                });
+            ui.label("Up to");
+            ui.add(
+                DragValue::new(&mut self.r_max)
+                    .clamp_range(0.01..=f32::INFINITY)
+                    .speed(0.05)
+                    .suffix(" m"),
+            );
+        });
+
+        let radial = Radial {
+            centre: particles[self.centre].pos.truncate(),
+            r_max: self.r_max,
+            samples: 256,
+        };
+        if !radial.is_symmetric(particles) {
+            ui.colored_label(
+                Color32::from_rgb(250, 135, 97),
+                "Not every particle shares this centre, so the formula won't match",
+            );
+        }
+
+        let sampled = radial.sample(&particles);
+        let analytic = radial.analytic(particles);
+        let values = |points: &[RadialPoint], f: fn(&RadialPoint) -> f32| {
+            Values::from_values_iter(points.iter().map(|p| Value::new(p.r, f(p))))
+        };
+        let v_color = Color32::from_rgb(90, 170, 255);
+        let e_color = Color32::from_rgb(250, 135, 97);
+        Plot::new("Radial Plot")
+            .width(400.0)
+            .height(250.0)
+            .legend(Legend::default())
+            .show(ui, |plot| {
+                plot.line(
+                    Line::new(values(&analytic, |p| p.voltage))
+                        .color(v_color)
+                        .name("V(r)"),
+                );
+                plot.line(
+                    Line::new(values(&analytic, |p| p.field))
+                        .color(e_color)
+                        .name("E(r)"),
+                );
+                // every few samples is enough to see they agree
+                let every = |points: &[RadialPoint]| -> Vec<RadialPoint> {
+                    points.iter().step_by(8).copied().collect()
+                };
+                let sampled = every(&sampled);
+                plot.points(
+                    Points::new(values(&sampled, |p| p.voltage))
+                        .color(v_color)
+                        .shape(MarkerShape::Circle)
+                        .radius(2.0)
+                        .name("V sampled"),
+                );
+                plot.points(
+                    Points::new(values(&sampled, |p| p.field))
+                        .color(e_color)
+                        .shape(MarkerShape::Circle)
+                        .radius(2.0)
+                        .name("E sampled"),
+                );
+                // only the particles at the centre have their surface at a fixed `r`
+                for p in particles.iter().filter(|p| radial.is_centred(p)) {
+                    plot.vline(
+                        VLine::new(p.radius)
+                            .color(Color32::from_gray(140))
+                            .style(LineStyle::dashed_dense())
+                            .name("Surface"),
+                    );
+                }
+            });
+    }
+}
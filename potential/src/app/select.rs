@@ -0,0 +1,291 @@
+use std::collections::BTreeSet;
+
+use archie_egui::egui;
+use egui::{Button, Color32, DragValue, Event, Key, Rect, Sense, Stroke};
+use glam::Vec2;
+
+use super::graph::{from_screen, to_screen};
+use potential::{
+    animation::Track,
+    edit::hit,
+    graph::Figure,
+    history::{Command, Document},
+    Particle, Transform,
+};
+
+/// "1 particle", "2 particles".
+fn particles(n: usize) -> String {
+    match n {
+        1 => "1 particle".to_owned(),
+        n => format!("{} particles", n),
+    }
+}
+
+/// The particles picked out in the editor, to be changed together,
+/// and the last ones that were copied.
+pub(super) struct Selection {
+    indices: BTreeSet<usize>,
+    clipboard: Vec<Particle>,
+    /// Where a box selection started, in the figure.
+    anchor: Option<Vec2>,
+    offset: [f32; 2],
+    /// In degrees.
+    angle: f32,
+    factor: f32,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self {
+            indices: BTreeSet::new(),
+            clipboard: Vec::new(),
+            anchor: None,
+            offset: [0.1, 0.0],
+            angle: 90.0,
+            factor: 2.0,
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.contains(&index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Forgets any particles that have gone.
+    pub fn retain(&mut self, len: usize) {
+        self.indices.retain(|&i| i < len);
+    }
+
+    /// The particles over the figure, picked out with the mouse.
+    ///
+    /// Clicking on one selects just that one, shift-clicking adds or takes it away,
+    /// and dragging out a box selects everything inside.
+    pub fn canvas_ui(&mut self, ui: &mut egui::Ui, figure: &Figure, particles: &[Particle]) {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        let selection = ui.visuals().selection;
+        let shift = ui.input().modifiers.shift;
+        let scale = rect.width() / (figure.x_max - figure.x_min);
+
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let pos = from_screen(figure, rect, pos);
+                let hit = hit(particles, pos, 4.0 / scale).map(|(i, _)| i);
+                match hit {
+                    Some(i) if shift => {
+                        if !self.indices.remove(&i) {
+                            self.indices.insert(i);
+                        }
+                    }
+                    Some(i) => {
+                        self.indices.clear();
+                        self.indices.insert(i);
+                    }
+                    None if shift => {}
+                    None => self.indices.clear(),
+                }
+            }
+        }
+        if response.drag_started() {
+            self.anchor = response
+                .interact_pointer_pos()
+                .map(|pos| from_screen(figure, rect, pos));
+        }
+        let pointer = response.interact_pointer_pos();
+        if let (Some(anchor), Some(pointer)) = (self.anchor, pointer) {
+            let area = Rect::from_two_pos(to_screen(figure, rect, anchor), pointer);
+            if response.drag_released() {
+                if !shift {
+                    self.indices.clear();
+                }
+                let inside = particles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| area.contains(to_screen(figure, rect, p.pos.truncate())));
+                self.indices.extend(inside.map(|(i, _)| i));
+                self.anchor = None;
+            } else {
+                painter.rect(
+                    area,
+                    0.0,
+                    selection.bg_fill.linear_multiply(0.25),
+                    selection.stroke,
+                );
+            }
+        }
+        if !response.dragged() {
+            self.anchor = None;
+        }
+
+        for (i, p) in particles.iter().enumerate() {
+            let centre = to_screen(figure, rect, p.pos.truncate());
+            let radius = (p.radius * scale).max(3.0);
+            if self.contains(i) {
+                painter.circle_filled(centre, radius + 3.0, selection.bg_fill);
+            }
+            let color = if p.value >= 0.0 {
+                Color32::from_rgb(214, 96, 77)
+            } else {
+                Color32::from_rgb(67, 147, 195)
+            };
+            painter.circle_stroke(centre, radius, Stroke::new(2.0, color));
+        }
+    }
+
+    /// Select all, copy, paste, duplicate and delete from the keyboard.
+    pub fn shortcuts(&mut self, input: &egui::InputState, doc: &Document) -> Option<Command> {
+        let command = input.modifiers.command;
+        let copy = command && input.key_pressed(Key::C);
+        let paste = command && input.key_pressed(Key::V);
+        // the clipboard may have turned these into events of their own
+        let copy = copy || input.events.iter().any(|e| matches!(e, Event::Copy));
+        let paste = paste || input.events.iter().any(|e| matches!(e, Event::Paste(_)));
+
+        if command && input.key_pressed(Key::A) {
+            self.indices = (0..doc.particles.len()).collect();
+        }
+        if copy {
+            self.copy(doc.particles);
+        }
+        if command && input.key_pressed(Key::D) {
+            return self.duplicate(doc);
+        }
+        if paste {
+            return self.paste(doc);
+        }
+        if input.key_pressed(Key::Delete) || input.key_pressed(Key::Backspace) {
+            return self.delete(doc);
+        }
+        None
+    }
+
+    /// What can be done to the selected particles.
+    pub fn ui(&mut self, ui: &mut egui::Ui, doc: &Document) -> Option<Command> {
+        let mut command = None;
+        ui.label(format!("{} selected", particles(self.indices.len())));
+        ui.horizontal(|ui| {
+            if ui.button("Copy").clicked() {
+                self.copy(doc.particles);
+            }
+            let pasted = !self.clipboard.is_empty();
+            if ui.add_enabled(pasted, Button::new("Paste")).clicked() {
+                command = self.paste(doc);
+            }
+            if ui.button("Duplicate").clicked() {
+                command = self.duplicate(doc);
+            }
+            if ui.button("Delete").clicked() {
+                command = self.delete(doc);
+            }
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            let [x, y] = &mut self.offset;
+            ui.add(DragValue::new(x).speed(0.01).prefix("x: "));
+            ui.add(DragValue::new(y).speed(0.01).prefix("y: "));
+            if ui.button("Move").clicked() {
+                let by = Vec2::from(self.offset);
+                command = self.transform("Move", Transform::Translate(by), doc);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut self.angle).speed(1.0).suffix("°"));
+            if ui.button("Rotate").clicked() {
+                let angle = self.angle.to_radians();
+                command = self.transform("Rotate", Transform::Rotate(angle), doc);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut self.factor).speed(0.01).prefix("×"));
+            if ui.button("Scale").clicked() {
+                command = self.transform("Scale", Transform::Scale(self.factor), doc);
+            }
+        });
+        if ui.button("Flip signs").clicked() {
+            command = self.transform("Flip", Transform::FlipSigns, doc);
+        }
+        command
+    }
+
+    fn copy(&mut self, particles: &[Particle]) {
+        if !self.indices.is_empty() {
+            self.clipboard = self.indices.iter().map(|&i| particles[i]).collect();
+        }
+    }
+
+    /// Adds what was copied a little way along from where it was,
+    /// and selects it.
+    fn paste(&mut self, doc: &Document) -> Option<Command> {
+        if self.clipboard.is_empty() {
+            return None;
+        }
+        let f = &*doc.figure;
+        let (width, height) = f.extent();
+        let step = 0.05 * Vec2::new(width.0, -height.0);
+        // pasting again carries on down the line
+        Transform::Translate(step).apply(&mut self.clipboard);
+
+        let start = doc.particles.len();
+        let commands = self
+            .clipboard
+            .iter()
+            .enumerate()
+            .map(|(i, &particle)| Command::Add {
+                index: start + i,
+                particle,
+                track: Track::default(),
+            })
+            .collect();
+        self.indices = (start..start + self.clipboard.len()).collect();
+        Some(Command::Group {
+            name: format!("Paste {}", particles(self.clipboard.len())),
+            commands,
+        })
+    }
+
+    fn duplicate(&mut self, doc: &Document) -> Option<Command> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        self.copy(doc.particles);
+        self.paste(doc)
+    }
+
+    fn delete(&mut self, doc: &Document) -> Option<Command> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        // from the back, so that the rest stay where they are
+        let commands = self
+            .indices
+            .iter()
+            .rev()
+            .map(|&i| Command::remove(doc, i))
+            .collect();
+        let name = format!("Delete {}", particles(self.indices.len()));
+        self.indices.clear();
+        Some(Command::Group { name, commands })
+    }
+
+    fn transform(&self, verb: &str, transform: Transform, doc: &Document) -> Option<Command> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        let old: Vec<Particle> = self.indices.iter().map(|&i| doc.particles[i]).collect();
+        let mut new = old.clone();
+        transform.apply(&mut new);
+        let commands = self
+            .indices
+            .iter()
+            .zip(old.into_iter().zip(new))
+            .map(|(&index, (old, new))| Command::Edit { index, old, new })
+            .collect();
+        Some(Command::Group {
+            name: format!("{} {}", verb, particles(self.indices.len())),
+            commands,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+//! Changes made to the particles by hand on the graph.
+
+use core::cmp::Ordering;
+
+use glam::Vec2;
+
+use crate::{
+    animation::Track, graph::Figure, history::Command, units::Charge, Particle, ParticleUnits,
+    Slice,
+};
+
+/// A change made to the particles by hand on the graph,
+/// in the coordinates of the slice.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// A new particle, negative if it was placed with shift held.
+    Place {
+        pos: Vec2,
+        negative: bool,
+    },
+    Move {
+        index: usize,
+        to: Vec2,
+    },
+    Resize {
+        index: usize,
+        radius: f32,
+    },
+    Delete(usize),
+}
+
+impl Edit {
+    /// Makes the edit to `particles`, which are seen through `slice`.
+    ///
+    /// Moves and resizes are made in place, to be recorded along with any other change.
+    /// Adding or deleting a particle is handed back as a command to be done.
+    pub fn apply(
+        self,
+        slice: &Slice,
+        figure: &Figure,
+        particles: &mut [Particle],
+        tracks: &[Track],
+    ) -> Option<Command> {
+        match self {
+            Edit::Place { pos, negative } => {
+                let charge = Charge(if negative { -1.0 } else { 1.0 });
+                let (width, _) = figure.extent();
+                let pos = slice.from_local(pos.extend(0.0));
+                let particle = Particle::with_units(charge, 0.05 * width, pos);
+                return Some(Command::Add {
+                    index: particles.len(),
+                    particle,
+                    track: Track::default(),
+                });
+            }
+            Edit::Move { index, to } => {
+                let p = &mut particles[index];
+                // keep it the same height off the slice
+                let z = slice.to_local(p.pos).z;
+                p.pos = slice.from_local(to.extend(z));
+            }
+            Edit::Resize { index, radius } => particles[index].radius = radius,
+            Edit::Delete(index) => {
+                return Some(Command::Remove {
+                    index,
+                    particle: particles[index],
+                    track: tracks.get(index).cloned().unwrap_or_default(),
+                })
+            }
+        }
+        None
+    }
+}
+
+/// What the mouse is holding on to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Grab {
+    /// The body of a particle, `offset` from its centre.
+    Body {
+        index: usize,
+        offset: Vec2,
+    },
+    Rim {
+        index: usize,
+    },
+}
+
+/// What would be picked up at `pos`, if anything.
+///
+/// Near the rim of a particle it's resized, anywhere else on it, it's moved.
+/// Particles too small on the screen to tell the difference can only be moved.
+///
+/// `particles` are where they're shown, which for an animated one isn't where it's kept,
+/// so whatever its track drives is left alone: one that's animated all over can't be grabbed.
+pub fn grab(particles: &[Particle], tracks: &[Track], pos: Vec2, reach: f32) -> Option<Grab> {
+    let (index, dist) = hit(particles, pos, reach)?;
+    let p = &particles[index];
+    let still = Track::default();
+    let track = tracks.get(index).unwrap_or(&still);
+    let moves = track.pos.iter().all(Option::is_none);
+    let resizes = track.radius.is_none();
+    let body = Grab::Body {
+        index,
+        offset: pos - p.pos.truncate(),
+    };
+    let rim = dist.abs() <= reach && p.radius > 3.0 * reach;
+    match (rim && resizes, moves) {
+        (true, _) => Some(Grab::Rim { index }),
+        (false, true) => Some(body),
+        (false, false) => None,
+    }
+}
+
+/// The particle closest to `pos`, as long as it's within `tolerance` of its surface,
+/// and how far it is from it.
+pub fn hit(particles: &[Particle], pos: Vec2, tolerance: f32) -> Option<(usize, f32)> {
+    particles
+        .iter()
+        .map(|p| p.dist(pos).0)
+        .enumerate()
+        .filter(|&(_, d)| d < tolerance)
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+}
+
+#[test]
+fn picking() {
+    use crate::animation::Signal;
+
+    let particles = [
+        Particle::new(1.0, 1.0, Vec2::ZERO),
+        Particle::new(1.0, 0.1, Vec2::new(3.0, 0.0)),
+    ];
+    let tracks = [Track::default(), Track::default()];
+    assert_eq!(hit(&particles, Vec2::new(2.95, 0.0), 0.1).unwrap().0, 1);
+    assert!(hit(&particles, Vec2::new(1.5, 0.0), 0.1).is_none());
+
+    // the rim of a big particle, but only the body of a small one
+    let rim = Vec2::new(1.0, 0.0);
+    assert_eq!(
+        grab(&particles, &tracks, rim, 0.1),
+        Some(Grab::Rim { index: 0 })
+    );
+    assert_eq!(
+        grab(&particles, &tracks, Vec2::new(3.0625, 0.0), 0.1),
+        Some(Grab::Body {
+            index: 1,
+            offset: Vec2::new(0.0625, 0.0)
+        })
+    );
+
+    // an animated radius can't be dragged, but the particle can still be moved
+    let signal = Signal::Ramp {
+        from: 0.5,
+        to: 1.0,
+        start: 0.0,
+        duration: 1.0,
+    };
+    let mut tracks = tracks;
+    tracks[0].radius = Some(signal.clone());
+    assert!(matches!(
+        grab(&particles, &tracks, rim, 0.1),
+        Some(Grab::Body { index: 0, .. })
+    ));
+    // and once it moves by itself too, there's nothing to hold on to
+    tracks[0].pos[1] = Some(signal);
+    assert_eq!(grab(&particles, &tracks, rim, 0.1), None);
+    assert_eq!(grab(&particles, &tracks, Vec2::ZERO, 0.1), None);
+}
+
+#[test]
+fn editing() {
+    use glam::Vec3;
+
+    let slice = Slice {
+        offset: 0.5,
+        ..Slice::default()
+    };
+    let figure = Figure::new(100.0, 100.0);
+    let mut particles = vec![Particle::new_3d(1.0, 0.1, Vec3::new(0.0, 0.0, 0.75))];
+    let tracks = vec![Track::default()];
+
+    // moved across the slice, it stays the same height off it
+    let z = slice.to_local(particles[0].pos).z;
+    let to = Vec2::new(1.0, -2.0);
+    let edit = Edit::Move { index: 0, to };
+    assert_eq!(edit.apply(&slice, &figure, &mut particles, &tracks), None);
+    let local = slice.to_local(particles[0].pos);
+    assert!(local.truncate().distance(to) < 1e-6 && (local.z - z).abs() < 1e-6);
+
+    let edit = Edit::Resize {
+        index: 0,
+        radius: 0.5,
+    };
+    assert_eq!(edit.apply(&slice, &figure, &mut particles, &tracks), None);
+    assert_eq!(particles[0].radius, 0.5);
+
+    // placed on the slice, at the end
+    let edit = Edit::Place {
+        pos: Vec2::ZERO,
+        negative: true,
+    };
+    match edit.apply(&slice, &figure, &mut particles, &tracks) {
+        Some(Command::Add {
+            index, particle, ..
+        }) => {
+            assert_eq!(index, 1);
+            assert!(particle.value < 0.0);
+            assert!(slice.to_local(particle.pos).length() < 1e-6);
+        }
+        _ => panic!("placing a particle should add it"),
+    }
+
+    let removed = particles[0];
+    assert_eq!(
+        Edit::Delete(0).apply(&slice, &figure, &mut particles, &tracks),
+        Some(Command::Remove {
+            index: 0,
+            particle: removed,
+            track: Track::default(),
+        })
+    );
+}
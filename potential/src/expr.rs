@@ -0,0 +1,651 @@
+//! Parsing and evaluating maths expressions, such as `2 * sin(t) + 1`.
+
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Num(f32),
+    Var(String),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Sqrt,
+    Exp,
+    Ln,
+    Log,
+    Abs,
+}
+
+impl Func {
+    const ALL: [Func; 14] = [
+        Func::Sin,
+        Func::Cos,
+        Func::Tan,
+        Func::Asin,
+        Func::Acos,
+        Func::Atan,
+        Func::Sinh,
+        Func::Cosh,
+        Func::Tanh,
+        Func::Sqrt,
+        Func::Exp,
+        Func::Ln,
+        Func::Log,
+        Func::Abs,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Func::Sin => "sin",
+            Func::Cos => "cos",
+            Func::Tan => "tan",
+            Func::Asin => "asin",
+            Func::Acos => "acos",
+            Func::Atan => "atan",
+            Func::Sinh => "sinh",
+            Func::Cosh => "cosh",
+            Func::Tanh => "tanh",
+            Func::Sqrt => "sqrt",
+            Func::Exp => "exp",
+            Func::Ln => "ln",
+            Func::Log => "log",
+            Func::Abs => "abs",
+        }
+    }
+
+    /// The derivative of the function, at `x`.
+    fn derivative(self, x: &Expr) -> Expr {
+        use Expr::*;
+        let x = || Box::new(x.clone());
+        let num = |n: f32| Box::new(Num(n));
+        let call = |f: Func| Box::new(Call(f, x()));
+        let square = |e: Box<Expr>| Box::new(Pow(e, num(2.0)));
+        match self {
+            Func::Sin => Call(Func::Cos, x()),
+            Func::Cos => Neg(call(Func::Sin)),
+            Func::Tan => Div(num(1.0), square(call(Func::Cos))),
+            Func::Asin => Div(
+                num(1.0),
+                Box::new(Call(Func::Sqrt, Box::new(Sub(num(1.0), square(x()))))),
+            ),
+            Func::Acos => Neg(Box::new(Func::Asin.derivative(&x()))),
+            Func::Atan => Div(num(1.0), Box::new(Add(num(1.0), square(x())))),
+            Func::Sinh => Call(Func::Cosh, x()),
+            Func::Cosh => Call(Func::Sinh, x()),
+            Func::Tanh => Div(num(1.0), square(call(Func::Cosh))),
+            Func::Sqrt => Div(num(1.0), Box::new(Mul(num(2.0), call(Func::Sqrt)))),
+            Func::Exp => Call(Func::Exp, x()),
+            Func::Ln => Div(num(1.0), x()),
+            Func::Log => Div(num(1.0), Box::new(Mul(x(), num(core::f32::consts::LN_10)))),
+            Func::Abs => Div(x(), call(Func::Abs)),
+        }
+    }
+
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Func::Sin => x.sin(),
+            Func::Cos => x.cos(),
+            Func::Tan => x.tan(),
+            Func::Asin => x.asin(),
+            Func::Acos => x.acos(),
+            Func::Atan => x.atan(),
+            Func::Sinh => x.sinh(),
+            Func::Cosh => x.cosh(),
+            Func::Tanh => x.tanh(),
+            Func::Sqrt => x.sqrt(),
+            Func::Exp => x.exp(),
+            Func::Ln => x.ln(),
+            Func::Log => x.log10(),
+            Func::Abs => x.abs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("unexpected `{found}` at {at}")]
+    Unexpected { found: char, at: usize },
+    #[error("expected {expected} at {at}")]
+    Expected { expected: &'static str, at: usize },
+    #[error("unknown function `{0}`")]
+    UnknownFunction(String),
+    #[error("invalid number `{0}`")]
+    Number(String),
+    #[error("empty expression")]
+    Empty,
+}
+
+impl ParseError {
+    /// Where in the source the error is, if it's at a single place.
+    pub fn at(&self) -> Option<usize> {
+        match self {
+            ParseError::Unexpected { at, .. } | ParseError::Expected { at, .. } => Some(*at),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum EvalError {
+    #[error("unknown variable `{0}`")]
+    UnknownVariable(String),
+}
+
+impl Expr {
+    /// Evaluates the expression, looking variables up by name.
+    pub fn eval(&self, vars: &[(&str, f32)]) -> Result<f32, EvalError> {
+        self.eval_with(&|name| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| *v))
+    }
+
+    /// Evaluates the expression, asking `lookup` for the value of each variable.
+    pub fn eval_with(&self, lookup: &impl Fn(&str) -> Option<f32>) -> Result<f32, EvalError> {
+        Ok(match self {
+            Expr::Num(x) => *x,
+            Expr::Var(name) => {
+                lookup(name).ok_or_else(|| EvalError::UnknownVariable(name.clone()))?
+            }
+            Expr::Neg(a) => -a.eval_with(lookup)?,
+            Expr::Add(a, b) => a.eval_with(lookup)? + b.eval_with(lookup)?,
+            Expr::Sub(a, b) => a.eval_with(lookup)? - b.eval_with(lookup)?,
+            Expr::Mul(a, b) => a.eval_with(lookup)? * b.eval_with(lookup)?,
+            Expr::Div(a, b) => a.eval_with(lookup)? / b.eval_with(lookup)?,
+            Expr::Pow(a, b) => a.eval_with(lookup)?.powf(b.eval_with(lookup)?),
+            Expr::Call(f, a) => f.apply(a.eval_with(lookup)?),
+        })
+    }
+
+    /// The derivative with respect to `var`, treating every other variable as a constant.
+    ///
+    /// The result is left as it comes out of the rules, see [`Expr::simplify`].
+    pub fn derivative(&self, var: &str) -> Expr {
+        use Expr::*;
+        let d = |e: &Expr| Box::new(e.derivative(var));
+        let b = |e: &Expr| Box::new(e.clone());
+        let num = |x: f32| Box::new(Num(x));
+        match self {
+            Num(_) => Num(0.0),
+            Var(name) => Num(if name == var { 1.0 } else { 0.0 }),
+            Neg(a) => Neg(d(a)),
+            Add(x, y) => Add(d(x), d(y)),
+            Sub(x, y) => Sub(d(x), d(y)),
+            // product rule
+            Mul(x, y) => Add(Box::new(Mul(d(x), b(y))), Box::new(Mul(b(x), d(y)))),
+            // quotient rule
+            Div(x, y) => Div(
+                Box::new(Sub(Box::new(Mul(d(x), b(y))), Box::new(Mul(b(x), d(y))))),
+                Box::new(Pow(b(y), num(2.0))),
+            ),
+            Pow(x, y) if !y.variables().contains(&var) => Mul(
+                Box::new(Mul(
+                    b(y),
+                    Box::new(Pow(b(x), Box::new(Sub(b(y), num(1.0))))),
+                )),
+                d(x),
+            ),
+            // x^y = exp(y ln x)
+            Pow(x, y) => Mul(
+                b(self),
+                Box::new(Add(
+                    Box::new(Mul(d(y), Box::new(Call(Func::Ln, b(x))))),
+                    Box::new(Div(Box::new(Mul(b(y), d(x))), b(x))),
+                )),
+            ),
+            // chain rule
+            Call(f, x) => Mul(Box::new(f.derivative(x)), d(x)),
+        }
+    }
+
+    /// Tidies up the expression, folding constants and dropping
+    /// anything that adds or multiplies by zero or one.
+    pub fn simplify(&self) -> Expr {
+        use Expr::*;
+        let is = |e: &Expr, x: f32| matches!(e, Num(n) if *n == x);
+        match self {
+            Num(_) | Var(_) => self.clone(),
+            Neg(a) => match a.simplify() {
+                Num(x) => Num(-x),
+                Neg(a) => *a,
+                a => Neg(Box::new(a)),
+            },
+            Add(x, y) => match (x.simplify(), y.simplify()) {
+                (Num(x), Num(y)) => Num(x + y),
+                (x, y) if is(&x, 0.0) => y,
+                (x, y) if is(&y, 0.0) => x,
+                (x, Neg(y)) => Sub(Box::new(x), y).simplify(),
+                (Neg(x), y) => Sub(Box::new(y), x).simplify(),
+                (x, y) => Add(Box::new(x), Box::new(y)),
+            },
+            Sub(x, y) => match (x.simplify(), y.simplify()) {
+                (Num(x), Num(y)) => Num(x - y),
+                (x, y) if x == y => Num(0.0),
+                (x, y) if is(&x, 0.0) => Neg(Box::new(y)).simplify(),
+                (x, y) if is(&y, 0.0) => x,
+                (x, Neg(y)) => Add(Box::new(x), y),
+                (x, y) => Sub(Box::new(x), Box::new(y)),
+            },
+            Mul(x, y) => match (x.simplify(), y.simplify()) {
+                (Num(x), Num(y)) => Num(x * y),
+                (x, y) if is(&x, 0.0) || is(&y, 0.0) => Num(0.0),
+                (x, y) if is(&x, 1.0) => y,
+                (x, y) if is(&y, 1.0) => x,
+                (x, y) if is(&x, -1.0) => Neg(Box::new(y)).simplify(),
+                (x, y) if is(&y, -1.0) => Neg(Box::new(x)).simplify(),
+                (Neg(x), Neg(y)) => Mul(x, y).simplify(),
+                (Neg(x), y) | (y, Neg(x)) => Neg(Box::new(Mul(x, Box::new(y)).simplify())),
+                // constants go in front, and gather together
+                (x, Num(n)) => Mul(Box::new(Num(n)), Box::new(x)).simplify(),
+                (Num(n), Mul(x, y)) if matches!(*x, Num(_)) => {
+                    Mul(Box::new(Mul(Box::new(Num(n)), x)), y).simplify()
+                }
+                (x, Mul(n, y)) if matches!(*n, Num(_)) => {
+                    Mul(Box::new(Mul(n, Box::new(x))), y).simplify()
+                }
+                // a single fraction
+                (Div(x, y), z) => Div(Box::new(Mul(x, Box::new(z))), y).simplify(),
+                (x, Div(y, z)) => Div(Box::new(Mul(Box::new(x), y)), z).simplify(),
+                (x, y) => Mul(Box::new(x), Box::new(y)),
+            },
+            Div(x, y) => match (x.simplify(), y.simplify()) {
+                (Num(x), Num(y)) if y != 0.0 => Num(x / y),
+                (x, y) if is(&x, 0.0) && !is(&y, 0.0) => Num(0.0),
+                (x, y) if is(&y, 1.0) => x,
+                (Neg(x), y) => Neg(Box::new(Div(x, Box::new(y)).simplify())),
+                (Div(x, y), z) => Div(x, Box::new(Mul(y, Box::new(z)))).simplify(),
+                (x, Div(y, z)) => Div(Box::new(Mul(Box::new(x), z)), y).simplify(),
+                // cancel constant factors
+                (Mul(n, x), Mul(m, y)) if matches!((&*n, &*m), (Num(_), Num(_))) => {
+                    Mul(Box::new(Div(n, m)), Box::new(Div(x, y))).simplify()
+                }
+                (x, y) => Div(Box::new(x), Box::new(y)),
+            },
+            Pow(x, y) => match (x.simplify(), y.simplify()) {
+                (Num(x), Num(y)) => Num(x.powf(y)),
+                (_, y) if is(&y, 0.0) => Num(1.0),
+                (x, y) if is(&y, 1.0) => x,
+                (Call(Func::Sqrt, x), y) if is(&y, 2.0) => *x,
+                (x, y) => Pow(Box::new(x), Box::new(y)),
+            },
+            Call(f, x) => Call(*f, Box::new(x.simplify())),
+        }
+    }
+
+    /// Every variable the expression refers to, without repeats.
+    pub fn variables(&self) -> Vec<&str> {
+        fn visit<'a>(e: &'a Expr, out: &mut Vec<&'a str>) {
+            match e {
+                Expr::Num(_) => {}
+                Expr::Var(name) => {
+                    if !out.contains(&name.as_str()) {
+                        out.push(name);
+                    }
+                }
+                Expr::Neg(a) | Expr::Call(_, a) => visit(a, out),
+                Expr::Add(a, b)
+                | Expr::Sub(a, b)
+                | Expr::Mul(a, b)
+                | Expr::Div(a, b)
+                | Expr::Pow(a, b) => {
+                    visit(a, out);
+                    visit(b, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        visit(self, &mut out);
+        out
+    }
+}
+
+impl Expr {
+    /// How tightly the expression binds, following the grammar of the parser.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Add(..) | Expr::Sub(..) => 1,
+            Expr::Mul(..) | Expr::Div(..) => 2,
+            Expr::Neg(_) => 3,
+            Expr::Num(x) if x.is_sign_negative() => 3,
+            Expr::Pow(..) => 4,
+            Expr::Num(_) | Expr::Var(_) | Expr::Call(..) => 5,
+        }
+    }
+
+    /// Writes `self`, in brackets if it binds less tightly than `min`.
+    fn fmt_within(&self, f: &mut fmt::Formatter<'_>, min: u8) -> fmt::Result {
+        if self.precedence() < min {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// Written out so that it parses back to the same expression,
+/// with only the brackets that are needed.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let binary = |f: &mut fmt::Formatter<'_>, a: &Expr, op: &str, b: &Expr, p: u8| {
+            a.fmt_within(f, p)?;
+            write!(f, " {} ", op)?;
+            // the parser groups to the left
+            b.fmt_within(f, p + 1)
+        };
+        match self {
+            Expr::Num(x) => write!(f, "{}", x),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Neg(a) => {
+                write!(f, "-")?;
+                a.fmt_within(f, 3)
+            }
+            Expr::Add(a, b) => binary(f, a, "+", b, 1),
+            Expr::Sub(a, b) => binary(f, a, "-", b, 1),
+            Expr::Mul(a, b) => binary(f, a, "*", b, 2),
+            Expr::Div(a, b) => binary(f, a, "/", b, 2),
+            Expr::Pow(a, b) => {
+                a.fmt_within(f, 5)?;
+                write!(f, "^")?;
+                b.fmt_within(f, 3)
+            }
+            Expr::Call(func, a) => write!(f, "{}({})", func.name(), a),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { src: s, at: 0 };
+        parser.skip_space();
+        if parser.peek().is_none() {
+            return Err(ParseError::Empty);
+        }
+        let e = parser.expr()?;
+        match parser.peek() {
+            None => Ok(e),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+}
+
+/// Recursive descent over the grammar
+///
+/// ```text
+/// expr  = term (('+' | '-') term)*
+/// term  = unary (('*' | '/') unary)*
+/// unary = '-' unary | power
+/// power = atom ('^' unary)?
+/// atom  = number | name | name '(' expr ')' | '(' expr ')'
+/// ```
+struct Parser<'a> {
+    src: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.at).copied()
+    }
+
+    /// Only ASCII is ever skipped over, so `at` always lands on a character.
+    fn unexpected(&self) -> ParseError {
+        ParseError::Unexpected {
+            found: self.src[self.at..].chars().next().unwrap_or_default(),
+            at: self.at,
+        }
+    }
+
+    fn skip_space(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+    /// Consumes `c` if it comes next.
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.at += 1;
+            self.skip_space();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut e = self.term()?;
+        loop {
+            if self.eat(b'+') {
+                e = Expr::Add(Box::new(e), Box::new(self.term()?));
+            } else if self.eat(b'-') {
+                e = Expr::Sub(Box::new(e), Box::new(self.term()?));
+            } else {
+                return Ok(e);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut e = self.unary()?;
+        loop {
+            if self.eat(b'*') {
+                e = Expr::Mul(Box::new(e), Box::new(self.unary()?));
+            } else if self.eat(b'/') {
+                e = Expr::Div(Box::new(e), Box::new(self.unary()?));
+            } else {
+                return Ok(e);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat(b'-') {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.atom()?;
+        if self.eat(b'^') {
+            Ok(Expr::Pow(Box::new(base), Box::new(self.unary()?)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        let start = self.at;
+        match self.peek() {
+            Some(b'(') => {
+                self.eat(b'(');
+                let e = self.expr()?;
+                self.expect(b')', "`)`")?;
+                Ok(e)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => {
+                while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'_') {
+                    self.at += 1;
+                }
+                let name = &self.src[start..self.at];
+                self.skip_space();
+                if self.eat(b'(') {
+                    let f = Func::ALL
+                        .into_iter()
+                        .find(|f| f.name() == name)
+                        .ok_or_else(|| ParseError::UnknownFunction(name.to_owned()))?;
+                    let arg = self.expr()?;
+                    self.expect(b')', "`)`")?;
+                    Ok(Expr::Call(f, Box::new(arg)))
+                } else {
+                    Ok(match name {
+                        "pi" => Expr::Num(core::f32::consts::PI),
+                        "e" => Expr::Num(core::f32::consts::E),
+                        _ => Expr::Var(name.to_owned()),
+                    })
+                }
+            }
+            Some(_) => Err(self.unexpected()),
+            None => Err(ParseError::Expected {
+                expected: "a value",
+                at: start,
+            }),
+        }
+    }
+
+    fn number(&mut self) -> Result<Expr, ParseError> {
+        let start = self.at;
+        let digits = |p: &mut Self| {
+            while matches!(p.peek(), Some(c) if c.is_ascii_digit() || c == b'.') {
+                p.at += 1;
+            }
+        };
+        digits(self);
+        // exponent, as long as it isn't the start of a name
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mark = self.at;
+            self.at += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.at += 1;
+            }
+            if matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                digits(self);
+            } else {
+                self.at = mark;
+            }
+        }
+        let text = &self.src[start..self.at];
+        self.skip_space();
+        text.parse()
+            .map(Expr::Num)
+            .map_err(|_| ParseError::Number(text.to_owned()))
+    }
+
+    fn expect(&mut self, c: u8, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(ParseError::Expected {
+                expected,
+                at: self.at,
+            })
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    let eval = |s: &str, t: f32| s.parse::<Expr>().unwrap().eval(&[("t", t)]).unwrap();
+    assert_eq!(eval("1 + 2 * 3", 0.0), 7.0);
+    assert_eq!(eval("(1 + 2) * 3", 0.0), 9.0);
+    assert_eq!(eval("2 ^ 3 ^ 2", 0.0), 512.0);
+    assert_eq!(eval("-2 ^ 2", 0.0), -4.0);
+    assert_eq!(eval("3e-1 * t", 10.0), 3.0);
+    assert_eq!(eval("abs(sin(pi * t))", 0.5), 1.0);
+    assert_eq!(eval("e", 0.0), core::f32::consts::E);
+
+    assert_eq!(
+        "1 +".parse::<Expr>(),
+        Err(ParseError::Expected {
+            expected: "a value",
+            at: 3
+        })
+    );
+    assert_eq!(
+        "foo(1)".parse::<Expr>(),
+        Err(ParseError::UnknownFunction("foo".to_owned()))
+    );
+    assert_eq!(
+        "(1 + 2".parse::<Expr>(),
+        Err(ParseError::Expected {
+            expected: "`)`",
+            at: 6
+        })
+    );
+    assert_eq!(
+        "2 × 3".parse::<Expr>(),
+        Err(ParseError::Unexpected { found: '×', at: 2 })
+    );
+    assert_eq!(
+        "x".parse::<Expr>().unwrap().eval(&[("t", 0.0)]),
+        Err(EvalError::UnknownVariable("x".to_owned()))
+    );
+}
+
+#[test]
+fn differentiate() {
+    let d = |s: &str, var: &str| {
+        let e: Expr = s.parse().unwrap();
+        e.derivative(var).simplify().to_string()
+    };
+    assert_eq!(d("x^2 - y^2", "x"), "2 * x");
+    assert_eq!(d("x^2 - y^2", "y"), "-(2 * y)");
+    assert_eq!(d("3 * x * y + y", "x"), "3 * y");
+    assert_eq!(
+        d("k * q / sqrt(x^2 + y^2)", "x"),
+        "-(k * q * x / (sqrt(x^2 + y^2) * (x^2 + y^2)))"
+    );
+    assert_eq!(d("sin(2 * x)", "x"), "2 * cos(2 * x)");
+
+    // checked against finite differences
+    let sources = [
+        "x^y",
+        "atan(y / x)",
+        "ln(abs(x)) * exp(-x)",
+        "acos(x / 4) - 2^x",
+    ];
+    for s in sources {
+        let e: Expr = s.parse().unwrap();
+        let dx = e.derivative("x").simplify();
+        let at = |x: f32| e.eval(&[("x", x), ("y", 1.5)]).unwrap();
+        let (x, h) = (1.3, 1e-3);
+        let expected = (at(x + h) - at(x - h)) / (2.0 * h);
+        let found = dx.eval(&[("x", x), ("y", 1.5)]).unwrap();
+        assert!(
+            (found - expected).abs() < 1e-2,
+            "{}: {} {}",
+            s,
+            found,
+            expected
+        );
+    }
+}
+
+#[test]
+fn display() {
+    let sources = [
+        "1 - (2 - x)",
+        "(a + b) * c",
+        "-x^2",
+        "(-x)^2",
+        "2^3^2",
+        "(2^3)^2",
+        "a / (b * c)",
+        "-(a + b) * 2",
+    ];
+    for s in sources {
+        let e: Expr = s.parse().unwrap();
+        assert_eq!(e.to_string(), s);
+        assert_eq!(e.to_string().parse::<Expr>(), Ok(e));
+    }
+}
@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use glam::Vec2;
+
+use crate::{
+    expr::{Expr, ParseError},
+    Distance, Field, Force, Voltage, COULOMB, GRAVITATIONAL,
+};
+
+/// A potential typed in by hand, such as `x^2 - y^2` or `k * q / sqrt(x^2 + y^2)`.
+///
+/// Besides `x`, `y` and the time `t`, it can use the constants `COULOMB` and
+/// `GRAVITATIONAL`. Any other name is a parameter that can be set freely.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Formula {
+    expr: Expr,
+    /// `E = -∇V`, worked out symbolically.
+    field: [Expr; 2],
+    /// Values of the parameters, in the order they first appear.
+    pub params: Vec<(String, f32)>,
+    /// The value of `t`.
+    pub time: f32,
+}
+
+impl Formula {
+    const CONSTANTS: [(&'static str, f32); 2] =
+        [("COULOMB", COULOMB), ("GRAVITATIONAL", GRAVITATIONAL)];
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// The x and y components of the field.
+    pub fn field(&self) -> &[Expr; 2] {
+        &self.field
+    }
+
+    /// Does the formula change over time?
+    pub fn uses_time(&self) -> bool {
+        self.expr.variables().contains(&"t")
+    }
+
+    /// Takes on the values of any parameters `other` shares with this formula.
+    pub fn keep_params(&mut self, other: &Formula) {
+        for (name, value) in &mut self.params {
+            if let Some((_, v)) = other.params.iter().find(|(n, _)| n == name) {
+                *value = *v;
+            }
+        }
+    }
+
+    pub fn voltage(&self, pos: Vec2) -> f32 {
+        self.eval(&self.expr, pos)
+    }
+
+    fn eval(&self, expr: &Expr, pos: Vec2) -> f32 {
+        let lookup = |name: &str| match name {
+            "x" => Some(pos.x),
+            "y" => Some(pos.y),
+            "t" => Some(self.time),
+            _ => Self::CONSTANTS
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| *v)
+                .or_else(|| {
+                    let (_, v) = self.params.iter().find(|(n, _)| n == name)?;
+                    Some(*v)
+                }),
+        };
+        // every variable has a value, so this can't fail
+        expr.eval_with(&lookup).unwrap_or(f32::NAN)
+    }
+}
+
+impl FromStr for Formula {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr: Expr = s.parse()?;
+        let params = expr
+            .variables()
+            .into_iter()
+            .filter(|v| !matches!(*v, "x" | "y" | "t"))
+            .filter(|v| !Self::CONSTANTS.iter().any(|(n, _)| n == v))
+            .map(|v| (v.to_owned(), 1.0))
+            .collect();
+        let field = ["x", "y"].map(|v| Expr::Neg(Box::new(expr.derivative(v))).simplify());
+        Ok(Self {
+            expr,
+            field,
+            params,
+            time: 0.0,
+        })
+    }
+}
+
+impl Field<Voltage> for Formula {
+    fn at(&self, pos: Vec2) -> Voltage {
+        Voltage(self.voltage(pos))
+    }
+}
+
+impl Field<Force> for Formula {
+    fn at(&self, pos: Vec2) -> Force {
+        let [x, y] = &self.field;
+        Force(Vec2::new(self.eval(x, pos), self.eval(y, pos)))
+    }
+}
+
+impl Field<Distance> for Formula {
+    /// There are no surfaces to keep away from.
+    fn at(&self, _: Vec2) -> Distance {
+        Distance(f32::INFINITY)
+    }
+}
+
+#[test]
+fn saddle() {
+    let mut formula: Formula = "a * (x^2 - y^2) + COULOMB * 0".parse().unwrap();
+    assert_eq!(formula.params, vec![("a".to_owned(), 1.0)]);
+    formula.params[0].1 = 2.0;
+    let pos = Vec2::new(1.0, 2.0);
+    assert_eq!(Field::<Voltage>::at(&formula, pos).0, -6.0);
+    assert_eq!(formula.field()[0].to_string(), "-(2 * a * x)");
+    assert_eq!(Field::<Force>::at(&formula, pos).0, Vec2::new(-4.0, 8.0));
+
+    let mut moving: Formula = "x - a * t".parse().unwrap();
+    assert!(moving.uses_time());
+    moving.keep_params(&formula);
+    moving.time = 3.0;
+    assert_eq!(moving.voltage(pos), -5.0);
+
+    assert_eq!(
+        "x^".parse::<Formula>(),
+        Err(ParseError::Expected {
+            expected: "a value",
+            at: 2
+        })
+    );
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+/// Colour maps used to turn a normalised value in `[0, 1]` into a colour.
+///
+/// The stops are sampled from the matplotlib / ColorBrewer maps
+/// and are linearly interpolated in between.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    /// Blue for negative, white at zero and red for positive values.
+    Diverging,
+    /// Optimised for colour vision deficiency.
+    Cividis,
+}
+
+impl Colormap {
+    pub const ALL: [Colormap; 4] = [
+        Colormap::Viridis,
+        Colormap::Magma,
+        Colormap::Diverging,
+        Colormap::Cividis,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Colormap::Viridis => "Viridis",
+            Colormap::Magma => "Magma",
+            Colormap::Diverging => "Diverging",
+            Colormap::Cividis => "Cividis",
+        }
+    }
+
+    /// Diverging maps should be centred on zero.
+    pub fn is_diverging(self) -> bool {
+        matches!(self, Colormap::Diverging)
+    }
+
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Diverging => &DIVERGING,
+            Colormap::Cividis => &CIVIDIS,
+        }
+    }
+
+    /// Colour at `t`, clamped to `[0, 1]`.
+    /// `NaN` maps to transparent.
+    pub fn at(self, t: f32) -> [u8; 4] {
+        if t.is_nan() {
+            return [0; 4];
+        }
+        let stops = self.stops();
+        let x = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let i = (x.floor() as usize).min(stops.len() - 2);
+        let f = x - i as f32;
+        let (a, b) = (stops[i], stops[i + 1]);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+        [lerp(a[0], b[0]), lerp(a[1], b[1]), lerp(a[2], b[2]), 255]
+    }
+}
+
+/// How values are transformed before they are normalised.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Scale {
+    Linear,
+    /// `log10(|v|)`, useful for the field magnitude.
+    Log,
+    /// Linear within `±threshold`, logarithmic outside, keeping the sign.
+    Symlog {
+        threshold: f32,
+    },
+}
+
+impl Scale {
+    pub fn apply(self, v: f32) -> f32 {
+        match self {
+            Scale::Linear => v,
+            Scale::Log => v.abs().max(f32::MIN_POSITIVE).log10(),
+            Scale::Symlog { threshold } => v.signum() * (1.0 + v.abs() / threshold).log10(),
+        }
+    }
+}
+
+/// The range of values mapped onto the colour map.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Range {
+    Fixed {
+        min: f32,
+        max: f32,
+    },
+    /// Picked from the sampled values, ignoring the outer percentiles.
+    /// Keeps the singularities at each particle from washing out the image.
+    Percentile {
+        low: f32,
+        high: f32,
+    },
+}
+
+impl Range {
+    /// Resolve the range against a set of samples.
+    /// Non-finite samples are ignored.
+    pub fn resolve(self, samples: &[f32]) -> (f32, f32) {
+        match self {
+            Range::Fixed { min, max } => (min, max),
+            Range::Percentile { low, high } => {
+                let mut sorted: Vec<f32> =
+                    samples.iter().copied().filter(|v| v.is_finite()).collect();
+                if sorted.is_empty() {
+                    return (0.0, 0.0);
+                }
+                sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+                let pick = |p: f32| {
+                    let i = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+                    sorted[i.round() as usize]
+                };
+                (pick(low), pick(high))
+            }
+        }
+    }
+}
+
+const VIRIDIS: [[u8; 3]; 9] = [
+    [0x44, 0x01, 0x54],
+    [0x47, 0x2d, 0x7b],
+    [0x3b, 0x52, 0x8b],
+    [0x2c, 0x72, 0x8e],
+    [0x21, 0x91, 0x8c],
+    [0x28, 0xae, 0x80],
+    [0x5e, 0xc9, 0x62],
+    [0xad, 0xdc, 0x30],
+    [0xfd, 0xe7, 0x25],
+];
+
+const MAGMA: [[u8; 3]; 9] = [
+    [0x00, 0x00, 0x04],
+    [0x1c, 0x10, 0x44],
+    [0x4f, 0x12, 0x7b],
+    [0x81, 0x25, 0x81],
+    [0xb5, 0x36, 0x7a],
+    [0xe5, 0x50, 0x64],
+    [0xfb, 0x87, 0x61],
+    [0xfe, 0xc2, 0x87],
+    [0xfc, 0xfd, 0xbf],
+];
+
+// ColorBrewer RdBu, reversed so that negative is blue
+const DIVERGING: [[u8; 3]; 9] = [
+    [0x21, 0x66, 0xac],
+    [0x43, 0x93, 0xc3],
+    [0x92, 0xc5, 0xde],
+    [0xd1, 0xe5, 0xf0],
+    [0xf7, 0xf7, 0xf7],
+    [0xfd, 0xdb, 0xc7],
+    [0xf4, 0xa5, 0x82],
+    [0xd6, 0x60, 0x4d],
+    [0xb2, 0x18, 0x2b],
+];
+
+const CIVIDIS: [[u8; 3]; 9] = [
+    [0x00, 0x22, 0x4e],
+    [0x12, 0x35, 0x70],
+    [0x3b, 0x49, 0x6c],
+    [0x57, 0x5d, 0x6d],
+    [0x70, 0x71, 0x73],
+    [0x8a, 0x87, 0x79],
+    [0xa6, 0x9d, 0x75],
+    [0xc4, 0xb5, 0x6c],
+    [0xfe, 0xe8, 0x38],
+];
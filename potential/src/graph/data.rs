@@ -0,0 +1,262 @@
+use std::io;
+
+use glam::Vec2;
+use serde::Serialize;
+
+use super::Linspace;
+use crate::{
+    scenario::{Body, FormulaText},
+    units::{Charge, ElectricField, ElectricPotential, Length},
+    FieldUnits, Particle, Slice,
+};
+
+/// The potential and field sampled over a grid, for analysis elsewhere.
+///
+/// Values are in SI units, volts and volts per metre.
+/// They are stored a row at a time, with `x` changing fastest and `y` increasing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridData {
+    pub xs: Vec<f32>,
+    pub ys: Vec<f32>,
+    pub voltage: Vec<f32>,
+    pub field: Vec<Vec2>,
+}
+
+impl GridData {
+    pub fn sample(xs: Linspace, ys: Linspace, source: &impl FieldUnits) -> Self {
+        let (xs, ys): (Vec<f32>, Vec<f32>) = (xs.collect(), ys.collect());
+        let n = xs.len() * ys.len();
+        let (mut voltage, mut field) = (Vec::with_capacity(n), Vec::with_capacity(n));
+        for &y in &ys {
+            for &x in &xs {
+                let pos = Vec2::new(x, y);
+                voltage.push(source.electric_potential(pos).0);
+                let [x, y] = source.electric_field(pos);
+                field.push(Vec2::new(x.0, y.0));
+            }
+        }
+        Self {
+            xs,
+            ys,
+            voltage,
+            field,
+        }
+    }
+
+    /// Every point on its own line, with a header row.
+    pub fn write_csv(&self, mut w: impl io::Write) -> io::Result<()> {
+        writeln!(w, "x,y,potential,field_x,field_y")?;
+        for (i, (v, e)) in self.voltage.iter().zip(&self.field).enumerate() {
+            let (x, y) = (self.xs[i % self.xs.len()], self.ys[i / self.xs.len()]);
+            writeln!(w, "{},{},{},{},{}", x, y, v, e.x, e.y)?;
+        }
+        Ok(())
+    }
+
+    /// A NumPy array of `float32`, shaped `(y, x, 3)`
+    /// with the potential and both components of the field along the last axis.
+    pub fn write_npy(&self, mut w: impl io::Write) -> io::Result<()> {
+        // https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+        let mut header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}, 3), }}",
+            self.ys.len(),
+            self.xs.len()
+        );
+        // the data has to start on a multiple of 64 bytes
+        let unpadded = 10 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+        header.push('\n');
+
+        w.write_all(b"\x93NUMPY\x01\x00")?;
+        w.write_all(&(header.len() as u16).to_le_bytes())?;
+        w.write_all(header.as_bytes())?;
+        for (v, e) in self.voltage.iter().zip(&self.field) {
+            for x in [*v, e.x, e.y] {
+                w.write_all(&x.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A legacy VTK file of structured points,
+    /// with the potential as scalars and the field as vectors.
+    pub fn write_vtk(&self, mut w: impl io::Write) -> io::Result<()> {
+        let step = |s: &[f32]| if s.len() > 1 { s[1] - s[0] } else { 1.0 };
+        writeln!(w, "# vtk DataFile Version 3.0")?;
+        writeln!(w, "potential")?;
+        writeln!(w, "ASCII")?;
+        writeln!(w, "DATASET STRUCTURED_POINTS")?;
+        writeln!(w, "DIMENSIONS {} {} 1", self.xs.len(), self.ys.len())?;
+        writeln!(w, "ORIGIN {} {} 0", self.xs[0], self.ys[0])?;
+        writeln!(w, "SPACING {} {} 1", step(&self.xs), step(&self.ys))?;
+        writeln!(w, "POINT_DATA {}", self.voltage.len())?;
+        writeln!(w, "SCALARS potential float 1")?;
+        writeln!(w, "LOOKUP_TABLE default")?;
+        for v in &self.voltage {
+            writeln!(w, "{}", v)?;
+        }
+        writeln!(w, "VECTORS field float")?;
+        for e in &self.field {
+            writeln!(w, "{} {} 0", e.x, e.y)?;
+        }
+        Ok(())
+    }
+
+    /// What the numbers mean, as JSON to go alongside the data.
+    ///
+    /// `x` and `y` run across `slice`, while `particles` are placed in space.
+    /// A `formula` is written out as it was typed, when the data came from one.
+    pub fn write_metadata(
+        &self,
+        particles: &[Particle],
+        slice: &Slice,
+        formula: Option<&FormulaText>,
+        mut w: impl io::Write,
+    ) -> io::Result<()> {
+        let (v, e) = (ElectricPotential::UNIT, ElectricField::UNIT);
+        let metadata = Metadata {
+            x: Axis::new(&self.xs),
+            y: Axis::new(&self.ys),
+            order: "x changes fastest, then y",
+            quantities: [
+                Column::new("potential", v),
+                Column::new("field_x", e),
+                Column::new("field_y", e),
+            ],
+            slice,
+            particles: particles.iter().map(Body::from).collect(),
+            formula,
+            units: Units {
+                charge: Charge::UNIT,
+                radius: Length::UNIT,
+                position: Length::UNIT,
+                angle: "rad",
+            },
+        };
+        serde_json::to_writer_pretty(&mut w, &metadata)?;
+        writeln!(w)
+    }
+}
+
+/// Everything [`GridData::write_metadata`] writes, in the order it's written.
+#[derive(Serialize)]
+struct Metadata<'a> {
+    x: Axis,
+    y: Axis,
+    order: &'static str,
+    quantities: [Column; 3],
+    slice: &'a Slice,
+    particles: Vec<Body>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formula: Option<&'a FormulaText>,
+    units: Units,
+}
+
+#[derive(Serialize)]
+struct Axis {
+    min: f32,
+    max: f32,
+    points: usize,
+    unit: &'static str,
+}
+
+impl Axis {
+    fn new(s: &[f32]) -> Self {
+        Self {
+            min: s[0],
+            max: s[s.len() - 1],
+            points: s.len(),
+            unit: Length::UNIT,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Column {
+    name: &'static str,
+    unit: &'static str,
+}
+
+impl Column {
+    fn new(name: &'static str, unit: &'static str) -> Self {
+        Self { name, unit }
+    }
+}
+
+#[derive(Serialize)]
+struct Units {
+    charge: &'static str,
+    radius: &'static str,
+    position: &'static str,
+    angle: &'static str,
+}
+
+#[test]
+fn formats() {
+    use super::linspace;
+    use crate::COULOMB;
+
+    let particles = [Particle::new(1e-9, 0.1, Vec2::ZERO)];
+    let data = GridData::sample(
+        linspace(-1.0, 1.0, 5),
+        linspace(0.0, 1.0, 3),
+        &&particles[..],
+    );
+    assert_eq!(data.voltage.len(), 15);
+    // one nanocoulomb a metre away
+    let v = data.voltage[4];
+    assert!((v - COULOMB * 1e-9).abs() < 1e-3);
+    assert!((data.field[4].x - COULOMB * 1e-9).abs() < 1e-3);
+
+    let mut csv = Vec::new();
+    data.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 16);
+    assert!(csv.lines().nth(5).unwrap().starts_with("1,0,"));
+    assert!(csv.lines().nth(6).unwrap().starts_with("-1,0.5,"));
+
+    let mut npy = Vec::new();
+    data.write_npy(&mut npy).unwrap();
+    assert!(npy.starts_with(b"\x93NUMPY"));
+    let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    assert!(String::from_utf8_lossy(&npy[10..10 + header_len]).contains("'shape': (3, 5, 3)"));
+    assert_eq!(npy.len(), 10 + header_len + 15 * 3 * 4);
+
+    let mut vtk = Vec::new();
+    data.write_vtk(&mut vtk).unwrap();
+    let vtk = String::from_utf8(vtk).unwrap();
+    assert!(vtk.contains("DIMENSIONS 5 3 1\nORIGIN -1 0 0\nSPACING 0.5 0.5 1\n"));
+
+    let metadata = |particles: &[Particle], slice, formula| {
+        let mut meta = Vec::new();
+        data.write_metadata(particles, slice, formula, &mut meta)
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&meta).unwrap()
+    };
+    let slice = Slice {
+        offset: 2.0,
+        ..Default::default()
+    };
+    let meta = metadata(&particles, &slice, None);
+    assert_eq!(meta["x"]["min"], -1.0);
+    assert_eq!(meta["x"]["points"], 5);
+    assert_eq!(meta["x"]["unit"], "m");
+    assert_eq!(meta["quantities"][2]["unit"], "V/m");
+    assert_eq!(meta["slice"]["offset"], 2.0);
+    assert_eq!(meta["particles"][0]["charge"], 1e-9);
+    assert_eq!(meta["units"]["charge"], "C");
+    assert!(meta.get("formula").is_none());
+
+    // numbers JSON can't hold come out as null, not as something unreadable
+    let broken = [Particle::new(f32::NAN, f32::INFINITY, Vec2::ZERO)];
+    let formula = FormulaText {
+        text: "a / r".to_owned(),
+        params: vec![("a".to_owned(), 2.0)],
+    };
+    let meta = metadata(&broken, &slice, Some(&formula));
+    assert!(meta["particles"][0]["charge"].is_null());
+    assert!(meta["particles"][0]["radius"].is_null());
+    assert_eq!(meta["formula"]["text"], "a / r");
+    assert_eq!(meta["formula"]["params"][0][1], 2.0);
+}
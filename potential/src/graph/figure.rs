@@ -1,21 +1,124 @@
-pub struct Figure {
-    pub width: f32,
-    pub height: f32,
-    pub x_min: f32,
-    pub x_max: f32,
-    pub y_min: f32,
-    pub y_max: f32,
+use glam::{DVec2, Vec2};
+
+use crate::{units::Length, Real};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Figure<F = f32> {
+    pub width: F,
+    pub height: F,
+    pub x_min: F,
+    pub x_max: F,
+    pub y_min: F,
+    pub y_max: F,
 }
 
-impl Figure {
-    pub fn new(width: f32, height: f32) -> Self {
+impl<F: Real> Figure<F> {
+    pub fn new(width: F, height: F) -> Self {
         Self {
             width,
             height,
-            x_min: -1.0,
-            x_max: 1.0,
-            y_min: -1.0,
-            y_max: 1.0,
+            x_min: -F::ONE,
+            x_max: F::ONE,
+            y_min: -F::ONE,
+            y_max: F::ONE,
         }
     }
 }
+
+impl Figure {
+    #[inline]
+    pub fn x_axis(&self) -> Vec2 {
+        Vec2::new(self.x_min, self.x_max)
+    }
+
+    #[inline]
+    pub fn y_axis(&self) -> Vec2 {
+        Vec2::new(self.y_min, self.y_max)
+    }
+
+    /// The extent of the figure along x.
+    #[inline]
+    pub fn x_range(&self) -> (Length, Length) {
+        (Length(self.x_min), Length(self.x_max))
+    }
+
+    /// The extent of the figure along y.
+    #[inline]
+    pub fn y_range(&self) -> (Length, Length) {
+        (Length(self.y_min), Length(self.y_max))
+    }
+
+    /// How wide and tall the figure is.
+    #[inline]
+    pub fn extent(&self) -> (Length, Length) {
+        let ((x_min, x_max), (y_min, y_max)) = (self.x_range(), self.y_range());
+        (x_max - x_min, y_max - y_min)
+    }
+
+    /// Maps a position in an image of size `res` into the figure.
+    /// The origin of the image is the top left corner.
+    pub fn to_world(&self, pos: Vec2, res: Vec2) -> Vec2 {
+        crate::map_pos(pos, res, self.x_axis(), self.y_axis())
+    }
+
+    /// The inverse of [`Figure::to_world`].
+    pub fn to_image(&self, pos: Vec2, res: Vec2) -> Vec2 {
+        let x = (pos.x - self.x_min) / (self.x_max - self.x_min) * res.x;
+        let y = (self.y_max - pos.y) / (self.y_max - self.y_min) * res.y;
+        Vec2::new(x, y)
+    }
+
+    /// The same figure, in double precision.
+    pub fn as_f64(&self) -> Figure<f64> {
+        Figure {
+            width: self.width as f64,
+            height: self.height as f64,
+            x_min: self.x_min as f64,
+            x_max: self.x_max as f64,
+            y_min: self.y_min as f64,
+            y_max: self.y_max as f64,
+        }
+    }
+}
+
+impl Figure<f64> {
+    #[inline]
+    pub fn x_axis(&self) -> DVec2 {
+        DVec2::new(self.x_min, self.x_max)
+    }
+
+    #[inline]
+    pub fn y_axis(&self) -> DVec2 {
+        DVec2::new(self.y_min, self.y_max)
+    }
+
+    /// Maps a position in an image of size `res` into the figure.
+    /// The origin of the image is the top left corner.
+    pub fn to_world(&self, pos: DVec2, res: DVec2) -> DVec2 {
+        let x = pos.x / res.x * (self.x_max - self.x_min) + self.x_min;
+        let y = pos.y / res.y * (self.y_min - self.y_max) + self.y_max;
+        DVec2::new(x, y)
+    }
+}
+
+#[test]
+fn precision() {
+    // a small window far from the origin
+    let mut figure: Figure = Figure::new(100.0, 100.0);
+    figure.x_min = 10_000.0;
+    figure.x_max = 10_000.01;
+    let res = Vec2::new(1000.0, 1000.0);
+    let (a, b) = (Vec2::new(500.0, 0.0), Vec2::new(501.0, 0.0));
+    let step = figure.to_world(b, res).x - figure.to_world(a, res).x;
+    let double = figure.as_f64();
+    let step64 = double.to_world(b.as_dvec2(), res.as_dvec2()).x
+        - double.to_world(a.as_dvec2(), res.as_dvec2()).x;
+    let expected = (double.x_max - double.x_min) / 1000.0;
+    assert!((step64 - expected).abs() < 1e-9);
+    assert!((step as f64 - expected).abs() > 0.1 * expected);
+    assert_eq!(figure.to_world(Vec2::ZERO, res), Vec2::new(10_000.0, 1.0));
+    assert_eq!(
+        double.to_world(DVec2::ZERO, res.as_dvec2()),
+        DVec2::new(10_000.0, 1.0)
+    );
+}
@@ -0,0 +1,208 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use super::{march, Colormap, Figure, Image, Range, Scale};
+use crate::{Field, Force, Voltage};
+
+/// The scalar that is sampled over the figure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Quantity {
+    /// Signed scalar potential.
+    Voltage,
+    /// Magnitude of the field.
+    Field,
+}
+
+impl Quantity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Quantity::Voltage => "Potential",
+            Quantity::Field => "|Field|",
+        }
+    }
+
+    pub fn eval<F>(self, field: &F, pos: Vec2) -> f32
+    where
+        F: Field<Voltage> + Field<Force>,
+    {
+        match self {
+            Quantity::Voltage => Field::<Voltage>::at(field, pos).0,
+            Quantity::Field => Field::<Force>::at(field, pos).0.length(),
+        }
+    }
+}
+
+/// Scalar values sampled at the centre of each pixel,
+/// row by row from the top left corner of the figure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Samples {
+    pub width: u32,
+    pub height: u32,
+    pub values: Vec<f32>,
+}
+
+impl Samples {
+    pub fn new(figure: &Figure, width: u32, height: u32, f: impl Fn(Vec2) -> f32) -> Self {
+        let res = Vec2::new(width as f32, height as f32);
+        let mut values = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                values.push(f(figure.to_world(pixel, res)));
+            }
+        }
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Where the samples cross `level`, in the figure they were taken over.
+    pub fn contour(&self, figure: &Figure, level: f32) -> Vec<Vec<Vec2>> {
+        let res = Vec2::new(self.width as f32, self.height as f32);
+        march(
+            (self.width as usize, self.height as usize),
+            level,
+            |x, y| self.values[y * self.width as usize + x],
+            |x, y| figure.to_world(Vec2::new(x as f32 + 0.5, y as f32 + 0.5), res),
+        )
+    }
+}
+
+/// Turns sampled values into an image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Heatmap {
+    pub colormap: Colormap,
+    pub scale: Scale,
+    pub range: Range,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            colormap: Colormap::Viridis,
+            scale: Scale::Linear,
+            range: Range::Percentile {
+                low: 2.0,
+                high: 98.0,
+            },
+        }
+    }
+}
+
+impl Heatmap {
+    /// The range of transformed values that spans the colour map.
+    /// Diverging maps are kept symmetric about zero.
+    pub fn limits(&self, samples: &Samples) -> (f32, f32) {
+        let (min, max) = match self.range {
+            // a scale like `Log` can reorder the values, so the percentiles
+            // are taken of the values as they're coloured
+            Range::Percentile { .. } => {
+                let scaled: Vec<f32> = samples
+                    .values
+                    .iter()
+                    .map(|&v| self.scale.apply(v))
+                    .collect();
+                self.range.resolve(&scaled)
+            }
+            Range::Fixed { min, max } => {
+                let (a, b) = (self.scale.apply(min), self.scale.apply(max));
+                (a.min(b), a.max(b))
+            }
+        };
+        if self.colormap.is_diverging() {
+            let m = min.abs().max(max.abs());
+            (-m, m)
+        } else {
+            (min, max)
+        }
+    }
+
+    pub fn colorize(&self, samples: &Samples) -> Image {
+        let (min, max) = self.limits(samples);
+        let span = max - min;
+        let pixels = samples
+            .values
+            .iter()
+            .map(|&v| {
+                let v = self.scale.apply(v);
+                let t = if span > 0.0 { (v - min) / span } else { 0.5 };
+                self.colormap.at(t)
+            })
+            .collect();
+        Image {
+            width: samples.width,
+            height: samples.height,
+            pixels,
+        }
+    }
+
+    pub fn render(
+        &self,
+        figure: &Figure,
+        width: u32,
+        height: u32,
+        f: impl Fn(Vec2) -> f32,
+    ) -> Image {
+        self.colorize(&Samples::new(figure, width, height, f))
+    }
+}
+
+#[test]
+fn diverging() {
+    let (particles, figure) = super::dipole();
+    let field = &particles[..];
+    let heatmap = Heatmap {
+        colormap: Colormap::Diverging,
+        ..Default::default()
+    };
+    let image = heatmap.render(&figure, 8, 8, |pos| Quantity::Voltage.eval(&field, pos));
+    // positive on the left is red, negative on the right is blue
+    let [r, _, b, _] = image.get(2, 4);
+    assert!(r > b);
+    let [r, _, b, _] = image.get(5, 4);
+    assert!(b > r);
+    // fading to white towards the middle
+    let brightness = |[r, g, b, _]: [u8; 4]| r as u32 + g as u32 + b as u32;
+    assert!(brightness(image.get(3, 4)) > brightness(image.get(2, 4)));
+    assert!(brightness(image.get(4, 4)) > brightness(image.get(5, 4)));
+}
+
+#[test]
+fn percentile() {
+    let values: Vec<f32> = (0..=100)
+        .map(|x| x as f32)
+        .chain([f32::NAN, f32::INFINITY])
+        .collect();
+    let range = Range::Percentile {
+        low: 10.0,
+        high: 90.0,
+    };
+    assert_eq!(range.resolve(&values), (10.0, 90.0));
+}
+
+#[test]
+fn log_of_signed() {
+    // a potential that dips further below zero than it rises above it
+    let samples = Samples {
+        width: 9,
+        height: 1,
+        values: (-5..=3).map(|x| x as f32).collect(),
+    };
+    let heatmap = Heatmap {
+        scale: Scale::Log,
+        range: Range::Percentile {
+            low: 0.0,
+            high: 100.0,
+        },
+        ..Default::default()
+    };
+    let (min, max) = heatmap.limits(&samples);
+    assert!(min < max);
+    assert_eq!(max, 5f32.log10());
+    // not one flat colour
+    let image = heatmap.colorize(&samples);
+    assert_ne!(image.get(0, 0), image.get(4, 0));
+    assert_ne!(image.get(0, 0), image.get(8, 0));
+}
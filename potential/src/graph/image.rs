@@ -0,0 +1,50 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// An RGBA image, stored row by row from the top left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 4]; (width * height) as usize],
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> [u8; 4] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        self.pixels[(y * self.width + x) as usize] = color;
+    }
+
+    /// Raw RGBA bytes, unmultiplied alpha.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.pixels)
+    }
+
+    /// Encodes the image as a PNG.
+    pub fn write_png(&self, w: impl Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(w, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(self.as_bytes())
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), png::EncodingError> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
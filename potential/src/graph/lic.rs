@@ -0,0 +1,150 @@
+use glam::Vec2;
+
+use super::{advance, Figure, Heatmap, Image, Range, Samples};
+use crate::{Field, Force};
+
+/// Line Integral Convolution.
+///
+/// Smears a white noise texture along the field lines,
+/// which gives the look of iron filings scattered around a magnet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Lic {
+    /// Number of pixels followed in each direction along the field line.
+    pub kernel: u32,
+    pub seed: u64,
+    /// Screen pixels to the side of each pixel of the texture,
+    /// higher is quicker to draw but blurrier.
+    pub downscale: u32,
+}
+
+impl Default for Lic {
+    fn default() -> Self {
+        Self {
+            kernel: 16,
+            seed: 0,
+            downscale: 2,
+        }
+    }
+}
+
+impl Lic {
+    /// Convolved noise in `[0, 1]`, in the same layout as [`Samples::new`].
+    pub fn convolve<F>(&self, figure: &Figure, width: u32, height: u32, field: &F) -> Samples
+    where
+        F: Field<Force>,
+    {
+        let noise = noise(width * height, self.seed);
+        let res = Vec2::new(width as f32, height as f32);
+        let size = Vec2::new(figure.x_max - figure.x_min, figure.y_max - figure.y_min);
+        // step a pixel at a time
+        let h = (size / res).min_element();
+        let lookup = |pos: Vec2| {
+            let x = (pos.x - figure.x_min) / size.x * res.x;
+            let y = (figure.y_max - pos.y) / size.y * res.y;
+            let inside = x >= 0.0 && y >= 0.0 && x < res.x && y < res.y;
+            inside.then(|| noise[(y as u32 * width + x as u32) as usize])
+        };
+
+        let mut values = Samples::new(figure, width, height, |start| {
+            let mut sum = 0.0;
+            let mut count = 0;
+            if let Some(n) = lookup(start) {
+                sum += n;
+                count += 1;
+            }
+            for h in [h, -h] {
+                let mut pos = start;
+                for _ in 0..self.kernel {
+                    match advance(field, pos, h).and_then(|p| Some((p, lookup(p)?))) {
+                        Some((p, n)) => {
+                            pos = p;
+                            sum += n;
+                            count += 1;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            if count > 0 {
+                sum / count as f32
+            } else {
+                0.5
+            }
+        });
+
+        // averaging pulls everything towards grey, so stretch the contrast back out
+        let (min, max) = Range::Percentile {
+            low: 1.0,
+            high: 99.0,
+        }
+        .resolve(&values.values);
+        if max > min {
+            for v in &mut values.values {
+                *v = ((*v - min) / (max - min)).clamp(0.0, 1.0);
+            }
+        }
+        values
+    }
+
+    /// The convolution shaded by a heatmap of the field magnitude.
+    pub fn render<F>(
+        &self,
+        figure: &Figure,
+        width: u32,
+        height: u32,
+        field: &F,
+        heatmap: &Heatmap,
+    ) -> Image
+    where
+        F: Field<Force>,
+    {
+        let lic = self.convolve(figure, width, height, field);
+        let magnitude = Samples::new(figure, width, height, |pos| field.at(pos).0.length());
+        let mut image = heatmap.colorize(&magnitude);
+        for (pixel, l) in image.pixels.iter_mut().zip(lic.values) {
+            let l = 0.15 + 0.85 * l;
+            for c in &mut pixel[..3] {
+                *c = (*c as f32 * l).round() as u8;
+            }
+        }
+        image
+    }
+}
+
+/// White noise in `[0, 1]` from a SplitMix64 generator.
+fn noise(len: u32, seed: u64) -> Vec<f32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            (z >> 40) as f32 / (1u64 << 24) as f32
+        })
+        .collect()
+}
+
+#[test]
+fn smears_along_the_field() {
+    use crate::Particle;
+
+    // field lines are radial, so pixels along a ray should agree
+    // far more than pixels around a circle
+    let particles = [Particle::new(1.0, 0.05, Vec2::ZERO)];
+    let lic = Lic {
+        kernel: 8,
+        seed: 7,
+        ..Default::default()
+    };
+    let samples = lic.convolve(&Figure::new(100.0, 100.0), 64, 64, &&particles[..]);
+    let at = |x: u32, y: u32| samples.values[(y * 64 + x) as usize];
+    let mut along = 0.0;
+    let mut across = 0.0;
+    for x in 40..60 {
+        along += (at(x, 32) - at(x + 1, 32)).abs();
+        across += (at(x, 32) - at(x, 33)).abs();
+    }
+    assert!(along < across);
+}
@@ -1,5 +1,43 @@
+mod colormap;
+mod data;
 mod figure;
+mod heatmap;
+mod image;
+mod lic;
 mod plot;
+mod profile;
+mod quiver;
+mod radial;
+mod render;
+mod streamline;
+mod svg;
+mod tikz;
+mod viewport;
 
+pub use colormap::*;
+pub use data::*;
 pub use figure::*;
-pub use plot::*;
\ No newline at end of file
+pub use heatmap::*;
+pub use image::*;
+pub use lic::*;
+pub use plot::*;
+pub use profile::*;
+pub use quiver::*;
+pub use radial::*;
+pub use render::*;
+pub use streamline::*;
+pub use tikz::*;
+pub use viewport::*;
+
+/// Two opposite charges either side of the origin, and a figure to see them in.
+#[cfg(test)]
+pub(crate) fn dipole() -> ([crate::Particle; 2], Figure) {
+    use crate::Particle;
+    use glam::Vec2;
+
+    let particles = [
+        Particle::new(1.0, 0.1, Vec2::new(-0.5, 0.0)),
+        Particle::new(-1.0, 0.1, Vec2::new(0.5, 0.0)),
+    ];
+    (particles, Figure::new(100.0, 100.0))
+}
@@ -1,26 +1,30 @@
+use std::collections::HashMap;
+
 use glam::{Vec2, Vec3};
 
+use crate::Real;
+
 #[derive(Clone, Copy)]
-pub struct Linspace {
-    from: f32,
-    inc: f32,
+pub struct Linspace<F = f32> {
+    from: F,
+    inc: F,
     points: u32,
     i: u32,
 }
 
-impl Linspace {
-    pub(crate) fn new(from: f32, to: f32, points: u32) -> Self {
+impl<F: Real> Linspace<F> {
+    pub(crate) fn new(from: F, to: F, points: u32) -> Self {
         Self {
             from,
-            inc: (to - from) / (points - 1) as f32,
+            inc: (to - from) / F::from_u32(points - 1),
             points,
             i: 0,
         }
     }
 }
 
-impl Iterator for Linspace {
-    type Item = f32;
+impl<F: Real> Iterator for Linspace<F> {
+    type Item = F;
 
     fn next(&mut self) -> Option<Self::Item> {
         let Self {
@@ -32,25 +36,167 @@ impl Iterator for Linspace {
         let x = if i == points {
             None
         } else {
-            Some(*from + *inc * (*i as f32))
+            Some(*from + *inc * F::from_u32(*i))
         };
         *i += 1;
         x
     }
 }
 
-pub fn linspace(from: f32, to: f32, points: u32) -> Linspace {
+pub fn linspace<F: Real>(from: F, to: F, points: u32) -> Linspace<F> {
     assert!(from < to);
     Linspace::new(from, to, points)
 }
 
 pub fn grid(xs: Linspace, ys: Linspace) -> impl Iterator<Item = Vec2> {
-    xs.flat_map(move |x| std::iter::repeat(x).zip(ys).map(|(y, x)| Vec2::new(x, y)))
+    xs.flat_map(move |x| std::iter::repeat(x).zip(ys).map(|(x, y)| Vec2::new(x, y)))
+}
+
+/// Round numbers between `min` and `max`, about `count` of them,
+/// for marking an axis.
+pub fn ticks(min: f32, max: f32, count: u32) -> Vec<f32> {
+    let span = max - min;
+    if !(span > 0.0 && span.is_finite()) || count == 0 {
+        return Vec::new();
+    }
+    let rough = span / count as f32;
+    let exponent = rough.log10().floor() as i32;
+    let magnitude = 10f32.powi(exponent.abs());
+    // dividing by an exact power of ten keeps 0.1 as close to 0.1 as it gets
+    let scale = |x: f32| match exponent < 0 {
+        true => x / magnitude,
+        false => x * magnitude,
+    };
+    let m = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .find(|&m| scale(m) >= rough)
+        .unwrap_or(10.0);
+    let step = scale(m);
+    // a little slack, so the ends aren't lost to rounding
+    let first = (min / step - 1e-3).ceil() as i32;
+    let last = (max / step + 1e-3).floor() as i32;
+    (first..=last).map(|i| scale(i as f32 * m)).collect()
 }
 
-pub fn contour(_g: impl Iterator<Item = Vec3>) {
+/// The lines where the values over `g` cross `level`, found with marching squares.
+///
+/// `g` should be laid out like a [`grid`], with the value in `z`.
+/// Lines that close up on themselves end where they started.
+pub fn contour(g: impl Iterator<Item = Vec3>, level: f32) -> Vec<Vec<Vec2>> {
+    let points: Vec<Vec3> = g.collect();
+    // each column of y comes before moving along x
+    let ny = match points.first() {
+        Some(first) => points.iter().take_while(|p| p.x == first.x).count(),
+        None => 0,
+    };
+    if ny < 2 || points.len() < 2 * ny {
+        return Vec::new();
+    }
+    let nx = points.len() / ny;
+    march(
+        (nx, ny),
+        level,
+        |i, j| points[i * ny + j].z,
+        |i, j| points[i * ny + j].truncate(),
+    )
+}
+
+/// A side of a cell, by the corner it starts at and whether it runs along `j`.
+type Edge = (usize, usize, bool);
+
+/// Marching squares over a `size` grid of `value`s sitting at `pos`.
+///
+/// Saddles are split by the value at the centre of the cell.
+/// Cells touching a value that isn't finite are left out.
+pub(crate) fn march(
+    size: (usize, usize),
+    level: f32,
+    value: impl Fn(usize, usize) -> f32,
+    pos: impl Fn(usize, usize) -> Vec2,
+) -> Vec<Vec<Vec2>> {
     // https://dmahr1.github.io/618-final/report.html
-    todo!()
+    let (nx, ny) = size;
+    let mut segments: Vec<[Edge; 2]> = Vec::new();
+    for i in 0..nx.saturating_sub(1) {
+        for j in 0..ny.saturating_sub(1) {
+            let v = [
+                value(i, j),
+                value(i + 1, j),
+                value(i + 1, j + 1),
+                value(i, j + 1),
+            ];
+            if !v.iter().all(|v| v.is_finite()) {
+                continue;
+            }
+            // going round the cell, edge k joins corner k to the next one
+            let edges = [
+                (i, j, false),
+                (i + 1, j, true),
+                (i, j + 1, false),
+                (i, j, true),
+            ];
+            let above = v.map(|v| v > level);
+            let crossed: Vec<usize> = (0..4).filter(|&k| above[k] != above[(k + 1) % 4]).collect();
+            match crossed[..] {
+                [a, b] => segments.push([edges[a], edges[b]]),
+                [_, _, _, _] => {
+                    // cut off the corners that disagree with the centre
+                    let centre = v.iter().sum::<f32>() / 4.0 > level;
+                    for k in (0..4).filter(|&k| above[k] != centre) {
+                        segments.push([edges[(k + 3) % 4], edges[k]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // join up the segments that share an edge
+    let mut ends: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (s, segment) in segments.iter().enumerate() {
+        for &edge in segment {
+            ends.entry(edge).or_default().push(s);
+        }
+    }
+    let mut used = vec![false; segments.len()];
+    let walk = |mut s: usize, mut edge: Edge, used: &mut [bool]| {
+        let mut line = vec![edge];
+        loop {
+            used[s] = true;
+            let [a, b] = segments[s];
+            edge = if a == edge { b } else { a };
+            line.push(edge);
+            match ends[&edge].iter().find(|&&next| !used[next]) {
+                Some(&next) => s = next,
+                None => break line,
+            }
+        }
+    };
+    let mut lines = Vec::new();
+    // lines running off the grid first, so that they aren't started in the middle
+    for s in 0..segments.len() {
+        if let Some(&edge) = segments[s].iter().find(|edge| ends[edge].len() == 1) {
+            if !used[s] {
+                lines.push(walk(s, edge, &mut used));
+            }
+        }
+    }
+    // anything left goes round in a loop
+    for s in 0..segments.len() {
+        if !used[s] {
+            lines.push(walk(s, segments[s][0], &mut used));
+        }
+    }
+
+    let cross = |(i, j, along): Edge| {
+        let (i2, j2) = if along { (i, j + 1) } else { (i + 1, j) };
+        let (a, b) = (value(i, j), value(i2, j2));
+        pos(i, j).lerp(pos(i2, j2), (level - a) / (b - a))
+    };
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().map(cross).collect())
+        .collect()
 }
 
 #[test]
@@ -58,7 +204,7 @@ fn api() {
     let x = linspace(-1.0, 1.0, 4);
     let y = linspace(-1.0, 1.0, 4);
     let g = grid(x, y);
-    let p = crate::particle::Particle::new(0.1, 0.1, Vec2::ZERO);
+    let p = crate::Particle::new(0.1, 0.1, Vec2::ZERO);
     let v = g.map(|pos| {
         pos.extend(
             match p.potential(pos) {
@@ -69,5 +215,33 @@ fn api() {
             .length(),
         )
     });
-    contour(v);
+    let lines = contour(v, 0.1);
+    assert!(!lines.is_empty());
+}
+
+#[test]
+fn circle() {
+    let n = 41;
+    let g = grid(linspace(-1.0, 1.0, n), linspace(-1.0, 1.0, n));
+    let lines = contour(g.map(|pos| pos.extend(pos.length())), 0.5);
+    // a single loop, coming back round to the start
+    assert_eq!(lines.len(), 1);
+    let line = &lines[0];
+    assert!(line.len() > 8);
+    assert!(line[0].distance(line[line.len() - 1]) < 1e-6);
+    assert!(line.iter().all(|p| (p.length() - 0.5).abs() < 0.01));
+}
+
+#[test]
+fn round_ticks() {
+    assert_eq!(ticks(-1.0, 1.0, 4), vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+    assert_eq!(ticks(0.05, 0.3, 3), vec![0.1, 0.2, 0.3]);
+    assert!(ticks(1.0, 1.0, 4).is_empty());
+}
+
+#[test]
+fn grid_order() {
+    let g: Vec<Vec2> = grid(linspace(0.0, 1.0, 2), linspace(2.0, 3.0, 2)).collect();
+    let expected = [(0.0, 2.0), (0.0, 3.0), (1.0, 2.0), (1.0, 3.0)];
+    assert_eq!(g, expected.map(|(x, y)| Vec2::new(x, y)));
 }
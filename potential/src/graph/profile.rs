@@ -0,0 +1,146 @@
+use std::io;
+
+use glam::Vec2;
+
+use super::{linspace, Figure};
+use crate::{Field, Force, Particle, Voltage};
+
+/// A straight segment through the scene that the field is sampled along.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LineCut {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub samples: u32,
+}
+
+/// The field at one point of a [`LineCut`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProfilePoint {
+    /// Distance along the cut from its start.
+    pub s: f32,
+    pub pos: Vec2,
+    pub voltage: f32,
+    pub magnitude: f32,
+    /// Component of the field along the cut.
+    pub component: f32,
+}
+
+impl LineCut {
+    /// Along the x axis at height `y`, across the whole figure.
+    pub fn horizontal(figure: &Figure, y: f32, samples: u32) -> Self {
+        Self {
+            from: Vec2::new(figure.x_min, y),
+            to: Vec2::new(figure.x_max, y),
+            samples,
+        }
+    }
+
+    /// Along the y axis at `x`, across the whole figure.
+    pub fn vertical(figure: &Figure, x: f32, samples: u32) -> Self {
+        Self {
+            from: Vec2::new(x, figure.y_min),
+            to: Vec2::new(x, figure.y_max),
+            samples,
+        }
+    }
+
+    #[inline]
+    pub fn length(&self) -> f32 {
+        self.from.distance(self.to)
+    }
+
+    pub fn sample<F>(&self, field: &F) -> Vec<ProfilePoint>
+    where
+        F: Field<Voltage> + Field<Force>,
+    {
+        let len = self.length();
+        if len <= 0.0 || self.samples < 2 {
+            return Vec::new();
+        }
+        let dir = (self.to - self.from) / len;
+        linspace(0.0, len, self.samples)
+            .map(|s| {
+                let pos = self.from + dir * s;
+                let Voltage(voltage) = field.at(pos);
+                let Force(e) = field.at(pos);
+                ProfilePoint {
+                    s,
+                    pos,
+                    voltage,
+                    magnitude: e.length(),
+                    component: e.dot(dir),
+                }
+            })
+            .collect()
+    }
+
+    /// Distances along the cut where it crosses the surface of a particle.
+    pub fn boundaries(&self, particles: &[Particle]) -> Vec<f32> {
+        let len = self.length();
+        if len <= 0.0 {
+            return Vec::new();
+        }
+        let dir = (self.to - self.from) / len;
+        let mut crossings: Vec<f32> = particles
+            .iter()
+            .flat_map(|p| {
+                // |from + s * dir - pos| = radius
+                let rel = self.from.extend(0.0) - p.pos;
+                let b = rel.truncate().dot(dir);
+                let c = rel.length_squared() - p.radius * p.radius;
+                let disc = b * b - c;
+                let roots = if disc >= 0.0 {
+                    let root = disc.sqrt();
+                    [Some(-b - root), Some(-b + root)]
+                } else {
+                    [None, None]
+                };
+                roots
+                    .into_iter()
+                    .flatten()
+                    .filter(move |s| (0.0..=len).contains(s))
+            })
+            .collect();
+        crossings.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+        crossings
+    }
+}
+
+/// Writes the profile with a header row.
+pub fn write_csv(points: &[ProfilePoint], mut w: impl io::Write) -> io::Result<()> {
+    writeln!(w, "s,x,y,potential,field_magnitude,field_component")?;
+    for p in points {
+        writeln!(
+            w,
+            "{},{},{},{},{},{}",
+            p.s, p.pos.x, p.pos.y, p.voltage, p.magnitude, p.component
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn through_a_particle() {
+    let particles = [Particle::new(2.0, 0.5, Vec2::ZERO)];
+    let cut = LineCut {
+        from: Vec2::new(-2.0, 0.0),
+        to: Vec2::new(2.0, 0.0),
+        samples: 81,
+    };
+    assert_eq!(cut.boundaries(&particles), vec![1.5, 2.5]);
+
+    let points = cut.sample(&&particles[..]);
+    assert_eq!(points.len(), 81);
+    // points away from the particle on the right
+    let right = points.last().unwrap();
+    assert!((right.voltage - 1.0).abs() < 1e-6);
+    assert!((right.component - 0.5).abs() < 1e-6);
+    // and against the cut on the left
+    assert!((points[0].component + 0.5).abs() < 1e-6);
+
+    let mut csv = Vec::new();
+    write_csv(&points, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 82);
+    assert!(csv.lines().last().unwrap().starts_with("4,2,0,1,0.5,0.5"));
+}
@@ -0,0 +1,161 @@
+use glam::Vec2;
+
+use super::{grid, linspace, Figure, Range};
+use crate::{Distance, Field, Force};
+
+/// How the length of an arrow follows the magnitude of the field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scaling {
+    Linear,
+    Log,
+    /// Every arrow has the same length,
+    /// the magnitude is only shown through [`Arrow::weight`].
+    Normalised,
+}
+
+impl Scaling {
+    pub const ALL: [Scaling; 3] = [Scaling::Linear, Scaling::Log, Scaling::Normalised];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scaling::Linear => "Linear",
+            Scaling::Log => "Log",
+            Scaling::Normalised => "Normalised",
+        }
+    }
+}
+
+/// A single arrow in world space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Arrow {
+    pub tail: Vec2,
+    pub tip: Vec2,
+    /// The two ends of the arrow head, both joined to the `tip`.
+    pub head: [Vec2; 2],
+    pub magnitude: f32,
+    /// Magnitude on a log scale, normalised to `[0, 1]` across the quiver.
+    /// Useful for colouring.
+    pub weight: f32,
+}
+
+impl Arrow {
+    /// The shaft and both sides of the head as line segments.
+    pub fn segments(&self) -> [[Vec2; 2]; 3] {
+        [
+            [self.tail, self.tip],
+            [self.head[0], self.tip],
+            [self.head[1], self.tip],
+        ]
+    }
+}
+
+/// Arrows of the field placed on a regular grid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quiver {
+    /// Distance between arrows, in world units.
+    pub spacing: f32,
+    pub scaling: Scaling,
+    /// Size of the head as a fraction of the arrow.
+    pub head: f32,
+    /// Arrows closer than this to a particle are dropped.
+    pub clearance: f32,
+}
+
+impl Default for Quiver {
+    fn default() -> Self {
+        Self {
+            spacing: 0.1,
+            scaling: Scaling::Linear,
+            head: 0.3,
+            clearance: 0.05,
+        }
+    }
+}
+
+impl Quiver {
+    pub fn arrows<F>(&self, figure: &Figure, field: &F) -> Vec<Arrow>
+    where
+        F: Field<Force> + Field<Distance>,
+    {
+        let s = self.spacing;
+        let nx = ((figure.x_max - figure.x_min) / s) as u32;
+        let ny = ((figure.y_max - figure.y_min) / s) as u32;
+        if s <= 0.0 || nx < 2 || ny < 2 {
+            return Vec::new();
+        }
+        let xs = linspace(figure.x_min + s / 2.0, figure.x_max - s / 2.0, nx);
+        let ys = linspace(figure.y_min + s / 2.0, figure.y_max - s / 2.0, ny);
+
+        let samples: Vec<(Vec2, Vec2, f32)> = grid(xs, ys)
+            .filter_map(|pos| {
+                let Distance(d) = field.at(pos);
+                if d < self.clearance {
+                    return None;
+                }
+                let Force(e) = field.at(pos);
+                (e != Vec2::ZERO && e.is_finite()).then_some((pos, e, d))
+            })
+            .collect();
+
+        let magnitudes: Vec<f32> = samples.iter().map(|(_, e, _)| e.length()).collect();
+        // the field blows up next to each particle, so ignore the strongest few
+        let (min, max) = Range::Percentile {
+            low: 0.0,
+            high: 95.0,
+        }
+        .resolve(&magnitudes);
+        let log = |m: f32| {
+            let (min, max) = (min.ln(), max.ln());
+            if max > min {
+                ((m.ln() - min) / (max - min)).clamp(0.0, 1.0)
+            } else {
+                1.0
+            }
+        };
+
+        let longest = 0.9 * s;
+        samples
+            .into_iter()
+            .map(|(pos, e, d)| {
+                let magnitude = e.length();
+                let weight = log(magnitude);
+                let len = match self.scaling {
+                    Scaling::Linear => longest * (magnitude / max).min(1.0),
+                    Scaling::Log => longest * weight,
+                    Scaling::Normalised => longest,
+                };
+                // never poke into a particle
+                let len = len.min(2.0 * (d - self.clearance));
+                let dir = e / magnitude;
+                let tail = pos - dir * len / 2.0;
+                let tip = pos + dir * len / 2.0;
+                let back = tip - dir * len * self.head;
+                let side = dir.perp() * len * self.head * 0.5;
+                Arrow {
+                    tail,
+                    tip,
+                    head: [back + side, back - side],
+                    magnitude,
+                    weight,
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn clearance() {
+    use crate::Particle;
+
+    let particles = [Particle::new(1.0, 0.2, Vec2::ZERO)];
+    let quiver = Quiver::default();
+    let arrows = quiver.arrows(&Figure::new(100.0, 100.0), &&particles[..]);
+    assert!(!arrows.is_empty());
+    for a in arrows {
+        for p in [a.tail, a.tip, a.head[0], a.head[1]] {
+            assert!(particles[0].dist(p).0 >= 0.0);
+        }
+        // pointing away from a positive charge
+        assert!((a.tip - a.tail).dot(a.tail) > 0.0);
+    }
+}
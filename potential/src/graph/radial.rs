@@ -0,0 +1,110 @@
+use glam::Vec2;
+
+use super::linspace;
+use crate::{Field, Force, Particle, Voltage};
+
+/// Potential and field against the distance from a centre,
+/// for spherically symmetric sources.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Radial {
+    pub centre: Vec2,
+    pub r_max: f32,
+    pub samples: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RadialPoint {
+    pub r: f32,
+    pub voltage: f32,
+    /// Outward component of the field.
+    pub field: f32,
+}
+
+impl Radial {
+    fn radii(&self) -> impl Iterator<Item = f32> {
+        let valid = self.r_max > 0.0 && self.samples >= 2;
+        valid
+            .then(|| linspace(0.0, self.r_max, self.samples))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Samples the field heading out from the centre along the x axis.
+    pub fn sample<F>(&self, field: &F) -> Vec<RadialPoint>
+    where
+        F: Field<Voltage> + Field<Force>,
+    {
+        self.radii()
+            .map(|r| {
+                let pos = self.centre + Vec2::new(r, 0.0);
+                let Voltage(voltage) = field.at(pos);
+                let Force(e) = field.at(pos);
+                RadialPoint {
+                    r,
+                    voltage,
+                    field: e.x,
+                }
+            })
+            .collect()
+    }
+
+    /// The exact result, taking every particle as a shell about the centre:
+    ///
+    /// `V(r) = Σ q / max(r, R)` and `E(r) = Σ q / r²` over the shells inside `r`.
+    pub fn analytic(&self, particles: &[Particle]) -> Vec<RadialPoint> {
+        self.radii()
+            .map(|r| {
+                let mut voltage = 0.0;
+                let mut field = 0.0;
+                for p in particles {
+                    voltage += p.value / r.max(p.radius);
+                    if r >= p.radius {
+                        field += p.value / (r * r);
+                    }
+                }
+                RadialPoint { r, voltage, field }
+            })
+            .collect()
+    }
+
+    /// Do all the particles share the centre?
+    /// If not, [`Radial::analytic`] doesn't describe them.
+    pub fn is_symmetric(&self, particles: &[Particle]) -> bool {
+        particles.iter().all(|p| self.is_centred(p))
+    }
+
+    /// Does the particle sit at the centre?
+    pub fn is_centred(&self, p: &Particle) -> bool {
+        const EPSILON: f32 = 1e-4;
+        p.pos.distance(self.centre.extend(0.0)) < EPSILON
+    }
+}
+
+#[test]
+fn shells() {
+    // a charge wrapped in a shell of the opposite charge
+    let particles = [
+        Particle::new(2.0, 0.5, Vec2::ONE),
+        Particle::new(-1.0, 1.5, Vec2::ONE),
+    ];
+    let radial = Radial {
+        centre: Vec2::ONE,
+        r_max: 3.0,
+        samples: 61,
+    };
+    assert!(radial.is_symmetric(&particles));
+    let elsewhere = Particle::new(1.0, 0.1, Vec2::ZERO);
+    assert!(!radial.is_centred(&elsewhere));
+    assert!(!radial.is_symmetric(&[particles[0], elsewhere]));
+
+    let sampled = radial.sample(&&particles[..]);
+    let analytic = radial.analytic(&particles);
+    for (s, a) in sampled.iter().zip(&analytic) {
+        assert!((s.voltage - a.voltage).abs() < 1e-4, "{:?} {:?}", s, a);
+        assert!((s.field - a.field).abs() < 1e-4, "{:?} {:?}", s, a);
+    }
+    // no field in the middle, only the inner charge between the shells
+    assert_eq!(analytic[5].field, 0.0);
+    let between = analytic[20];
+    assert!((between.field - 2.0 / (between.r * between.r)).abs() < 1e-5);
+}
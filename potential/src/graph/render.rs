@@ -0,0 +1,273 @@
+use glam::Vec2;
+
+use super::{ticks, Colormap, Figure, Heatmap, Image, Quantity, Range, Samples, Streamlines};
+use crate::{Distance, Field, Force, Formula, Particle, Voltage};
+
+pub(super) const BACKGROUND: [u8; 4] = [255, 255, 255, 255];
+pub(super) const CONTOUR: [u8; 4] = [40, 40, 40, 255];
+pub(super) const FIELD_LINE: [u8; 4] = [90, 90, 90, 255];
+pub(super) const AXIS: [u8; 4] = [0, 0, 0, 255];
+pub(super) const POSITIVE: [u8; 4] = [214, 96, 77, 255];
+pub(super) const NEGATIVE: [u8; 4] = [67, 147, 195, 255];
+pub(super) const SIGN: [u8; 4] = [255, 255, 255, 255];
+
+/// What a [`Scene`] is a picture of:
+/// a field to sample, and the particles making it to mark on top, if there are any.
+pub trait Subject: Field<Voltage> + Field<Force> + Field<Distance> {
+    fn particles(&self) -> &[Particle];
+}
+
+impl Subject for &[Particle] {
+    fn particles(&self) -> &[Particle] {
+        self
+    }
+}
+
+impl Subject for Formula {
+    fn particles(&self) -> &[Particle] {
+        &[]
+    }
+}
+
+/// What goes into a picture of the field,
+/// drawn in software so that no window or GPU is needed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scene {
+    /// What the heatmap and contours show.
+    pub quantity: Quantity,
+    /// Plain white when there isn't one.
+    pub heatmap: Option<Heatmap>,
+    /// How many contours to draw, evenly spaced over the sampled values.
+    pub contours: u32,
+    pub streamlines: Option<Streamlines>,
+    pub particles: bool,
+    pub axes: bool,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            quantity: Quantity::Voltage,
+            heatmap: Some(Heatmap {
+                colormap: Colormap::Diverging,
+                ..Default::default()
+            }),
+            contours: 10,
+            streamlines: Some(Streamlines::default()),
+            particles: true,
+            axes: true,
+        }
+    }
+}
+
+impl Scene {
+    /// The values the contours are drawn at,
+    /// spread between the outer percentiles so the particles don't hog them.
+    pub fn levels(&self, samples: &Samples) -> Vec<f32> {
+        let range = Range::Percentile {
+            low: 2.0,
+            high: 98.0,
+        };
+        let (min, max) = range.resolve(&samples.values);
+        let n = self.contours;
+        (1..=n)
+            .map(|i| min + (max - min) * i as f32 / (n + 1) as f32)
+            .collect()
+    }
+
+    /// The quantity at the centre of each pixel.
+    pub fn sample<F>(&self, figure: &Figure, field: &F, width: u32, height: u32) -> Samples
+    where
+        F: Field<Voltage> + Field<Force>,
+    {
+        Samples::new(figure, width, height, |pos| self.quantity.eval(field, pos))
+    }
+
+    /// Every contour, one level after another.
+    pub fn contours(&self, figure: &Figure, samples: &Samples) -> Vec<Vec<Vec2>> {
+        self.levels(samples)
+            .into_iter()
+            .flat_map(|level| samples.contour(figure, level))
+            .collect()
+    }
+
+    pub fn render(
+        &self,
+        figure: &Figure,
+        subject: &impl Subject,
+        width: u32,
+        height: u32,
+    ) -> Image {
+        let samples = self.sample(figure, subject, width, height);
+        let mut canvas = Canvas {
+            image: match &self.heatmap {
+                Some(heatmap) => heatmap.colorize(&samples),
+                None => Image {
+                    width,
+                    height,
+                    pixels: vec![BACKGROUND; (width * height) as usize],
+                },
+            },
+            figure: *figure,
+        };
+
+        for line in self.contours(figure, &samples) {
+            canvas.polyline(&line, 1.0, CONTOUR);
+        }
+        if let Some(streamlines) = &self.streamlines {
+            for line in streamlines.place(figure, subject) {
+                canvas.polyline(&line, 1.0, FIELD_LINE);
+            }
+        }
+        if self.axes {
+            canvas.axes();
+        }
+        if self.particles {
+            for p in subject.particles() {
+                canvas.particle(p);
+            }
+        }
+        canvas.image
+    }
+}
+
+/// An image that can be drawn on in world units.
+struct Canvas {
+    image: Image,
+    figure: Figure,
+}
+
+impl Canvas {
+    fn res(&self) -> Vec2 {
+        Vec2::new(self.image.width as f32, self.image.height as f32)
+    }
+
+    fn to_image(&self, pos: Vec2) -> Vec2 {
+        self.figure.to_image(pos, self.res())
+    }
+
+    /// Pixels per world unit.
+    fn scale(&self) -> f32 {
+        self.image.width as f32 / (self.figure.x_max - self.figure.x_min)
+    }
+
+    /// Lays `color` over a pixel, `coverage` of the way.
+    fn blend(&mut self, x: i32, y: i32, color: [u8; 4], coverage: f32) {
+        let (w, h) = (self.image.width as i32, self.image.height as i32);
+        if coverage <= 0.0 || !(0..w).contains(&x) || !(0..h).contains(&y) {
+            return;
+        }
+        let a = coverage.min(1.0) * color[3] as f32 / 255.0;
+        let dst = self.image.get(x as u32, y as u32);
+        let mix = |s: u8, d: u8| (s as f32 * a + d as f32 * (1.0 - a)).round() as u8;
+        let alpha = (255.0 * a + dst[3] as f32 * (1.0 - a)).round() as u8;
+        let out = [
+            mix(color[0], dst[0]),
+            mix(color[1], dst[1]),
+            mix(color[2], dst[2]),
+            alpha,
+        ];
+        self.image.set(x as u32, y as u32, out);
+    }
+
+    /// Covers every pixel near the box from `min` to `max`
+    /// by how far its centre is inside the shape at `dist`.
+    fn fill(&mut self, min: Vec2, max: Vec2, color: [u8; 4], dist: impl Fn(Vec2) -> f32) {
+        let (x0, y0) = (min.x.floor() as i32 - 1, min.y.floor() as i32 - 1);
+        let (x1, y1) = (max.x.ceil() as i32 + 1, max.y.ceil() as i32 + 1);
+        let (w, h) = (self.image.width as i32, self.image.height as i32);
+        for y in y0.max(0)..=y1.min(h - 1) {
+            for x in x0.max(0)..=x1.min(w - 1) {
+                let centre = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                self.blend(x, y, color, 0.5 - dist(centre));
+            }
+        }
+    }
+
+    /// A line between two points in the image.
+    fn segment(&mut self, a: Vec2, b: Vec2, width: f32, color: [u8; 4]) {
+        if !(a.is_finite() && b.is_finite()) {
+            return;
+        }
+        let r = width / 2.0;
+        let ab = b - a;
+        let len2 = ab.length_squared();
+        self.fill(a.min(b) - r, a.max(b) + r, color, |p| {
+            let t = match len2 > 0.0 {
+                true => ((p - a).dot(ab) / len2).clamp(0.0, 1.0),
+                false => 0.0,
+            };
+            p.distance(a + ab * t) - r
+        });
+    }
+
+    fn polyline(&mut self, points: &[Vec2], width: f32, color: [u8; 4]) {
+        for w in points.windows(2) {
+            let (a, b) = (self.to_image(w[0]), self.to_image(w[1]));
+            self.segment(a, b, width, color);
+        }
+    }
+
+    /// A filled circle in the image.
+    fn disc(&mut self, centre: Vec2, radius: f32, color: [u8; 4]) {
+        self.fill(centre - radius, centre + radius, color, |p| {
+            p.distance(centre) - radius
+        });
+    }
+
+    /// The particle, with a `+` or `-` on it to tell its sign without colour.
+    fn particle(&mut self, p: &Particle) {
+        let centre = self.to_image(p.pos.truncate());
+        let radius = (p.radius * self.scale()).max(3.0);
+        let (color, positive) = match p.value >= 0.0 {
+            true => (POSITIVE, true),
+            false => (NEGATIVE, false),
+        };
+        self.disc(centre, radius + 1.0, AXIS);
+        self.disc(centre, radius, color);
+        let arm = radius * 0.5;
+        let width = (radius * 0.2).max(1.0);
+        self.segment(centre - Vec2::X * arm, centre + Vec2::X * arm, width, SIGN);
+        if positive {
+            self.segment(centre - Vec2::Y * arm, centre + Vec2::Y * arm, width, SIGN);
+        }
+    }
+
+    /// The x and y axes through the origin, or along the edges when it's out of view,
+    /// with ticks at round numbers.
+    fn axes(&mut self) {
+        const TICK: f32 = 4.0;
+        let f = self.figure;
+        let origin = Vec2::new(0f32.clamp(f.x_min, f.x_max), 0f32.clamp(f.y_min, f.y_max));
+        let o = self.to_image(origin);
+        let res = self.res();
+        self.segment(Vec2::new(0.0, o.y), Vec2::new(res.x, o.y), 1.0, AXIS);
+        self.segment(Vec2::new(o.x, 0.0), Vec2::new(o.x, res.y), 1.0, AXIS);
+        for x in ticks(f.x_min, f.x_max, 8) {
+            let p = self.to_image(Vec2::new(x, origin.y));
+            self.segment(p - Vec2::Y * TICK, p + Vec2::Y * TICK, 1.0, AXIS);
+        }
+        for y in ticks(f.y_min, f.y_max, 8) {
+            let p = self.to_image(Vec2::new(origin.x, y));
+            self.segment(p - Vec2::X * TICK, p + Vec2::X * TICK, 1.0, AXIS);
+        }
+    }
+}
+
+#[test]
+fn headless() {
+    let (particles, figure) = super::dipole();
+    let image = Scene::default().render(&figure, &&particles[..], 120, 100);
+    assert_eq!((image.width, image.height), (120, 100));
+    // the particles are drawn in their colours, over the top of everything else
+    let at = |pos: Vec2| {
+        let p = figure.to_image(pos, Vec2::new(120.0, 100.0));
+        image.get(p.x as u32, p.y as u32)
+    };
+    assert_eq!(at(Vec2::new(-0.45, -0.04)), POSITIVE);
+    assert_eq!(at(Vec2::new(0.55, -0.04)), NEGATIVE);
+
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+}
@@ -0,0 +1,234 @@
+use glam::Vec2;
+
+use super::Figure;
+use crate::{Distance, Field, Force};
+
+/// Unit direction of the field, if there is one.
+#[inline]
+pub fn direction<F: Field<Force>>(field: &F, pos: Vec2) -> Option<Vec2> {
+    let Force(e) = field.at(pos);
+    let len = e.length();
+    (len > 0.0 && len.is_finite()).then(|| e / len)
+}
+
+/// Moves `h` along the field line through `pos`,
+/// backwards against the field when `h` is negative.
+///
+/// Uses RK4 on the normalised field, so each step covers the same distance
+/// regardless of how strong the field is.
+/// Returns `None` when the line ends, inside a particle or at a null point.
+pub fn advance<F: Field<Force>>(field: &F, pos: Vec2, h: f32) -> Option<Vec2> {
+    let k1 = direction(field, pos)?;
+    let k2 = direction(field, pos + k1 * h / 2.0)?;
+    let k3 = direction(field, pos + k2 * h / 2.0)?;
+    let k4 = direction(field, pos + k3 * h)?;
+    Some(pos + (k1 + 2.0 * k2 + 2.0 * k3 + k4) * h / 6.0)
+}
+
+/// Evenly spaced field lines, placed with the algorithm from
+/// Jobard & Lefer, "Creating Evenly-Spaced Streamlines of Arbitrary Density" (1997).
+///
+/// New lines are seeded `separation` away from existing ones
+/// and stop growing once they come within `test * separation` of another line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Streamlines {
+    /// Distance between neighbouring lines, in world units.
+    pub separation: f32,
+    /// Fraction of `separation` that lines can approach each other.
+    pub test: f32,
+    /// Integration step as a fraction of `separation`.
+    pub step: f32,
+    /// Longest a single line can grow in each direction.
+    pub max_steps: u32,
+}
+
+impl Default for Streamlines {
+    fn default() -> Self {
+        Self {
+            separation: 0.1,
+            test: 0.5,
+            step: 0.2,
+            max_steps: 1000,
+        }
+    }
+}
+
+impl Streamlines {
+    pub fn place<F>(&self, figure: &Figure, field: &F) -> Vec<Vec<Vec2>>
+    where
+        F: Field<Force> + Field<Distance>,
+    {
+        if self.separation <= 0.0 {
+            return Vec::new();
+        }
+        let d_sep = self.separation;
+        let d_test = self.test * d_sep;
+        let mut grid = Cells::new(figure, d_sep);
+        let mut lines: Vec<Vec<Vec2>> = Vec::new();
+
+        // seeds from a coarse grid make sure every region of the figure gets visited
+        let coarse = {
+            let (w, h) = (figure.x_max - figure.x_min, figure.y_max - figure.y_min);
+            let s = 4.0 * d_sep;
+            let (nx, ny) = ((w / s).max(1.0) as u32, (h / s).max(1.0) as u32);
+            (0..ny).flat_map(move |j| {
+                (0..nx).map(move |i| {
+                    Vec2::new(
+                        figure.x_min + w * (i as f32 + 0.5) / nx as f32,
+                        figure.y_min + h * (j as f32 + 0.5) / ny as f32,
+                    )
+                })
+            })
+        };
+
+        for seed in coarse {
+            if !grid.is_clear(seed, d_sep) {
+                continue;
+            }
+            let line = match self.trace(figure, field, &grid, seed, d_test) {
+                Some(line) => line,
+                None => continue,
+            };
+            grid.insert(&line);
+            lines.push(line);
+
+            // then grow outwards from the new lines, one separation to either side
+            let mut current = lines.len() - 1;
+            while current < lines.len() {
+                let mut seeds = Vec::new();
+                for w in lines[current].windows(2) {
+                    let normal = (w[1] - w[0]).normalize_or_zero().perp() * d_sep;
+                    seeds.extend([w[0] + normal, w[0] - normal]);
+                }
+                for seed in seeds {
+                    if !figure_contains(figure, seed) || !grid.is_clear(seed, d_sep) {
+                        continue;
+                    }
+                    if let Some(line) = self.trace(figure, field, &grid, seed, d_test) {
+                        grid.insert(&line);
+                        lines.push(line);
+                    }
+                }
+                current += 1;
+            }
+        }
+        lines
+    }
+
+    /// Follows the field line through `seed` in both directions,
+    /// until it runs into a particle or another line.
+    fn trace<F>(
+        &self,
+        figure: &Figure,
+        field: &F,
+        grid: &Cells,
+        seed: Vec2,
+        d_test: f32,
+    ) -> Option<Vec<Vec2>>
+    where
+        F: Field<Force> + Field<Distance>,
+    {
+        let h = self.step * self.separation;
+        let [mut forward, mut backward] = [h, -h].map(|h| {
+            let mut half = Vec::new();
+            let mut pos = seed;
+            for _ in 0..self.max_steps {
+                match advance(field, pos, h) {
+                    Some(p)
+                        if figure_contains(figure, p)
+                            && grid.is_clear(p, d_test)
+                            && Field::<Distance>::at(field, p).0 >= 0.0 =>
+                    {
+                        half.push(p);
+                        pos = p;
+                    }
+                    _ => break,
+                }
+            }
+            half
+        });
+        if forward.len() + backward.len() < 2 {
+            return None;
+        }
+        backward.reverse();
+        backward.push(seed);
+        backward.append(&mut forward);
+        Some(backward)
+    }
+}
+
+#[inline]
+fn figure_contains(figure: &Figure, pos: Vec2) -> bool {
+    (figure.x_min..=figure.x_max).contains(&pos.x) && (figure.y_min..=figure.y_max).contains(&pos.y)
+}
+
+/// Points bucketed into square cells, for quick distance tests.
+struct Cells {
+    min: Vec2,
+    size: f32,
+    nx: i32,
+    ny: i32,
+    cells: Vec<Vec<Vec2>>,
+}
+
+impl Cells {
+    fn new(figure: &Figure, size: f32) -> Self {
+        let nx = ((figure.x_max - figure.x_min) / size).ceil().max(1.0) as i32;
+        let ny = ((figure.y_max - figure.y_min) / size).ceil().max(1.0) as i32;
+        Self {
+            min: Vec2::new(figure.x_min, figure.y_min),
+            size,
+            nx,
+            ny,
+            cells: vec![Vec::new(); (nx * ny) as usize],
+        }
+    }
+
+    fn cell(&self, pos: Vec2) -> (i32, i32) {
+        let c = ((pos - self.min) / self.size).floor();
+        (
+            (c.x as i32).clamp(0, self.nx - 1),
+            (c.y as i32).clamp(0, self.ny - 1),
+        )
+    }
+
+    fn insert(&mut self, line: &[Vec2]) {
+        for &p in line {
+            let (x, y) = self.cell(p);
+            self.cells[(y * self.nx + x) as usize].push(p);
+        }
+    }
+
+    /// Is there no point within `radius` of `pos`?
+    /// `radius` must be no larger than the cell size.
+    fn is_clear(&self, pos: Vec2, radius: f32) -> bool {
+        let (cx, cy) = self.cell(pos);
+        let r2 = radius * radius;
+        for y in (cy - 1).max(0)..=(cy + 1).min(self.ny - 1) {
+            for x in (cx - 1).max(0)..=(cx + 1).min(self.nx - 1) {
+                let cell = &self.cells[(y * self.nx + x) as usize];
+                if cell.iter().any(|p| p.distance_squared(pos) < r2) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[test]
+fn evenly_spaced() {
+    let (particles, figure) = super::dipole();
+    let streamlines = Streamlines::default();
+    let lines = streamlines.place(&figure, &&particles[..]);
+    assert!(lines.len() > 10);
+    // lines never get closer than the test distance to one another
+    let d_test = streamlines.test * streamlines.separation;
+    for (i, a) in lines.iter().enumerate() {
+        for b in &lines[i + 1..] {
+            for p in a {
+                assert!(b.iter().all(|q| p.distance(*q) >= d_test * 0.99));
+            }
+        }
+    }
+}
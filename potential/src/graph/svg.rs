@@ -0,0 +1,271 @@
+use std::fmt::{self, Write};
+
+use glam::Vec2;
+
+use super::{
+    render::{AXIS, CONTOUR, FIELD_LINE, NEGATIVE, POSITIVE, SIGN},
+    ticks, Figure, Scene, Subject,
+};
+use crate::scientific::{Format, Notation, Sci};
+
+/// `#rrggbb`, dropping the alpha.
+fn hex([r, g, b, _]: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// A tick label in metres, with an SI prefix.
+fn label(x: f32) -> String {
+    let format = Format::default().notation(Notation::Si);
+    let text = Sci(x).format(format).to_string();
+    match text.contains(' ') {
+        // the prefix is already spaced out
+        true => format!("{}m", text),
+        false => format!("{} m", text),
+    }
+}
+
+/// `x,y x,y ...` for a `points` attribute.
+fn points(points: impl Iterator<Item = Vec2>) -> String {
+    let mut s = String::new();
+    for p in points {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+        let _ = write!(s, "{:.2},{:.2}", p.x, p.y);
+    }
+    s
+}
+
+impl Scene {
+    /// The scene as an SVG, `width` by `height` pixels.
+    ///
+    /// Everything but the heatmap is drawn as vectors,
+    /// the heatmap is embedded as a PNG underneath.
+    pub fn svg(&self, figure: &Figure, subject: &impl Subject, width: u32, height: u32) -> String {
+        let mut svg = String::new();
+        // writing to a `String` never fails
+        let _ = self.write_svg(&mut svg, figure, subject, width, height);
+        svg
+    }
+
+    fn write_svg(
+        &self,
+        w: &mut String,
+        figure: &Figure,
+        subject: &impl Subject,
+        width: u32,
+        height: u32,
+    ) -> fmt::Result {
+        let res = Vec2::new(width as f32, height as f32);
+        let to_image = |pos: Vec2| figure.to_image(pos, res);
+        let samples = self.sample(figure, subject, width, height);
+
+        writeln!(
+            w,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        )?;
+        match &self.heatmap {
+            Some(heatmap) => {
+                let mut png = Vec::new();
+                if heatmap.colorize(&samples).write_png(&mut png).is_ok() {
+                    writeln!(
+                        w,
+                        r#"<image width="{}" height="{}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+                        width,
+                        height,
+                        base64::encode(&png)
+                    )?;
+                }
+            }
+            None => writeln!(w, r#"<rect width="100%" height="100%" fill="white"/>"#)?,
+        }
+
+        writeln!(w, r#"<g fill="none" stroke="{}">"#, hex(CONTOUR))?;
+        for line in self.contours(figure, &samples) {
+            let line = points(line.into_iter().map(to_image));
+            writeln!(w, r#"<polyline points="{}"/>"#, line)?;
+        }
+        writeln!(w, "</g>")?;
+
+        if let Some(streamlines) = &self.streamlines {
+            let lines = streamlines.place(figure, subject);
+            writeln!(w, r#"<g fill="none" stroke="{}">"#, hex(FIELD_LINE))?;
+            for line in &lines {
+                let line = points(line.iter().map(|&p| to_image(p)));
+                writeln!(w, r#"<polyline points="{}"/>"#, line)?;
+            }
+            writeln!(w, "</g>")?;
+            // an arrow halfway along each line, pointing the way the field goes
+            writeln!(w, r#"<g fill="{}">"#, hex(FIELD_LINE))?;
+            for line in &lines {
+                let mid = line.len() / 2;
+                if mid + 1 >= line.len() {
+                    continue;
+                }
+                let (a, b) = (to_image(line[mid]), to_image(line[mid + 1]));
+                let dir = (b - a).normalize_or_zero();
+                if dir == Vec2::ZERO {
+                    continue;
+                }
+                let head = [
+                    a + dir * 4.0,
+                    a - dir * 4.0 + dir.perp() * 3.0,
+                    a - dir * 4.0 - dir.perp() * 3.0,
+                ];
+                writeln!(w, r#"<polygon points="{}"/>"#, points(head.into_iter()))?;
+            }
+            writeln!(w, "</g>")?;
+        }
+
+        if self.axes {
+            self.write_axes(w, figure, res)?;
+        }
+
+        if self.particles {
+            let scale = res.x / (figure.x_max - figure.x_min);
+            for p in subject.particles() {
+                let c = to_image(p.pos.truncate());
+                let r = (p.radius * scale).max(3.0);
+                let color = if p.value >= 0.0 { POSITIVE } else { NEGATIVE };
+                writeln!(
+                    w,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}" stroke="{}"/>"#,
+                    c.x,
+                    c.y,
+                    r,
+                    hex(color),
+                    hex(AXIS)
+                )?;
+                // the sign, so it can be told without colour
+                let arm = r * 0.5;
+                write!(
+                    w,
+                    r#"<path d="M{:.2} {:.2}h{:.2}"#,
+                    c.x - arm,
+                    c.y,
+                    2.0 * arm
+                )?;
+                if p.value >= 0.0 {
+                    write!(w, r#"M{:.2} {:.2}v{:.2}"#, c.x, c.y - arm, 2.0 * arm)?;
+                }
+                writeln!(
+                    w,
+                    r#"" stroke="{}" stroke-width="{:.2}"/>"#,
+                    hex(SIGN),
+                    (r * 0.2).max(1.0)
+                )?;
+            }
+        }
+
+        writeln!(w, "</svg>")
+    }
+
+    /// The x and y axes through the origin, or along the edges when it's out of view,
+    /// with labelled ticks at round numbers.
+    fn write_axes(&self, w: &mut String, figure: &Figure, res: Vec2) -> fmt::Result {
+        const TICK: f32 = 4.0;
+        let f = figure;
+        let origin = Vec2::new(0f32.clamp(f.x_min, f.x_max), 0f32.clamp(f.y_min, f.y_max));
+        let o = f.to_image(origin, res);
+        // labels go on whichever side has more room
+        let below = o.y < res.y / 2.0;
+        let right = o.x < res.x / 2.0;
+
+        writeln!(w, r#"<g stroke="{}">"#, hex(AXIS))?;
+        writeln!(
+            w,
+            r#"<path d="M0 {:.2}H{:.2}M{:.2} 0V{:.2}"/>"#,
+            o.y, res.x, o.x, res.y
+        )?;
+        let xs = ticks(f.x_min, f.x_max, 8);
+        let ys = ticks(f.y_min, f.y_max, 8);
+        for &x in &xs {
+            let p = f.to_image(Vec2::new(x, origin.y), res);
+            writeln!(
+                w,
+                r#"<path d="M{:.2} {:.2}v{:.2}"/>"#,
+                p.x,
+                p.y - TICK,
+                2.0 * TICK
+            )?;
+        }
+        for &y in &ys {
+            let p = f.to_image(Vec2::new(origin.x, y), res);
+            writeln!(
+                w,
+                r#"<path d="M{:.2} {:.2}h{:.2}"/>"#,
+                p.x - TICK,
+                p.y,
+                2.0 * TICK
+            )?;
+        }
+        writeln!(w, "</g>")?;
+
+        writeln!(
+            w,
+            r#"<g font-family="sans-serif" font-size="10" fill="{}">"#,
+            hex(AXIS)
+        )?;
+        for &x in &xs {
+            let p = f.to_image(Vec2::new(x, origin.y), res);
+            let y = if below {
+                p.y + TICK + 10.0
+            } else {
+                p.y - TICK - 2.0
+            };
+            writeln!(
+                w,
+                r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+                p.x,
+                y,
+                label(x)
+            )?;
+        }
+        for &y in &ys {
+            // the x axis already marks where it crosses
+            if y == origin.y {
+                continue;
+            }
+            let p = f.to_image(Vec2::new(origin.x, y), res);
+            let (x, anchor) = match right {
+                true => (p.x + TICK + 2.0, "start"),
+                false => (p.x - TICK - 2.0, "end"),
+            };
+            writeln!(
+                w,
+                r#"<text x="{:.2}" y="{:.2}" text-anchor="{}">{}</text>"#,
+                x,
+                p.y + 3.5,
+                anchor,
+                label(y)
+            )?;
+        }
+        writeln!(w, "</g>")
+    }
+}
+
+#[test]
+fn vectors() {
+    let (particles, figure) = super::dipole();
+    let scene = Scene {
+        heatmap: None,
+        ..Default::default()
+    };
+    let svg = scene.svg(&figure, &&particles[..], 200, 200);
+    assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    assert!(!svg.contains("<image"));
+    assert_eq!(svg.matches("<circle").count(), 2);
+    assert!(svg.matches("<polyline").count() > 10);
+    assert!(svg.contains(">500 mm</text>"));
+    assert!(svg.contains(">-1.00 m</text>"));
+
+    let svg = Scene::default().svg(&figure, &&particles[..], 20, 20);
+    assert!(svg.contains("data:image/png;base64,"));
+
+    // a formula has contours, but nothing to mark on top
+    let formula: crate::Formula = "x^2 - y^2".parse().unwrap();
+    let svg = scene.svg(&figure, &formula, 50, 50);
+    assert_eq!(svg.matches("<circle").count(), 0);
+    assert!(svg.matches("<polyline").count() > 5);
+}
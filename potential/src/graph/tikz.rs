@@ -0,0 +1,221 @@
+use std::fmt::{self, Write};
+
+use glam::Vec2;
+
+use super::{ticks, Figure, Scene, Subject};
+use crate::scientific::{Exponent, Format, Sci};
+
+/// How a TikZ picture is laid out and styled.
+///
+/// Each style is a list of TikZ options,
+/// named on the picture so that they can be changed again in the document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tikz {
+    /// Width of the picture in centimetres,
+    /// the height follows so that the figure isn't stretched.
+    pub width: f32,
+    /// Wrap the picture in a `standalone` document, ready to compile.
+    /// Otherwise the document needs the `arrows.meta` and `decorations.markings` libraries.
+    pub standalone: bool,
+    pub contour: String,
+    pub field_line: String,
+    pub positive: String,
+    pub negative: String,
+    pub axis: String,
+}
+
+impl Default for Tikz {
+    fn default() -> Self {
+        Self {
+            width: 10.0,
+            standalone: true,
+            contour: "thin, black!80".to_owned(),
+            field_line: "thin, black!50".to_owned(),
+            positive: "draw=black, fill=red!60".to_owned(),
+            negative: "draw=black, fill=blue!50".to_owned(),
+            axis: "thin, black".to_owned(),
+        }
+    }
+}
+
+/// A tick label, in plain numbers unless they get too long.
+fn label(x: f32) -> String {
+    if x == 0.0 || (1e-3..1e4).contains(&x.abs()) {
+        x.to_string()
+    } else {
+        Sci(x)
+            .format(Format::default().exponent(Exponent::Latex))
+            .to_string()
+    }
+}
+
+/// `(x,y) -- (x,y) -- ...`, dropping points too close to the last to matter.
+fn path(points: impl Iterator<Item = Vec2>, min: f32) -> String {
+    let mut s = String::new();
+    let mut last: Option<Vec2> = None;
+    for p in points {
+        if matches!(last, Some(l) if l.distance(p) < min) {
+            continue;
+        }
+        if last.is_some() {
+            s.push_str(" -- ");
+        }
+        let _ = write!(s, "({:.3},{:.3})", p.x, p.y);
+        last = Some(p);
+    }
+    s
+}
+
+impl Scene {
+    /// The scene as a TikZ picture.
+    ///
+    /// The heatmap is left out, everything else is drawn as paths.
+    pub fn tikz(&self, figure: &Figure, subject: &impl Subject, tikz: &Tikz) -> String {
+        let mut tex = String::new();
+        // writing to a `String` never fails
+        let _ = self.write_tikz(&mut tex, figure, subject, tikz);
+        tex
+    }
+
+    fn write_tikz(
+        &self,
+        w: &mut String,
+        figure: &Figure,
+        subject: &impl Subject,
+        tikz: &Tikz,
+    ) -> fmt::Result {
+        let f = figure;
+        // everything is drawn in centimetres from the bottom left corner
+        let scale = tikz.width / (f.x_max - f.x_min);
+        let (width, height) = (tikz.width, (f.y_max - f.y_min) * scale);
+        let to_cm = |p: Vec2| (p - Vec2::new(f.x_min, f.y_min)) * scale;
+        // fine enough for a few hundred points across
+        let detail = width / 400.0;
+
+        if tikz.standalone {
+            writeln!(w, r"\documentclass[tikz]{{standalone}}")?;
+            writeln!(w, r"\usetikzlibrary{{arrows.meta, decorations.markings}}")?;
+            writeln!(w, r"\begin{{document}}")?;
+        }
+        writeln!(w, r"\begin{{tikzpicture}}[")?;
+        writeln!(w, "  contour/.style={{{}}},", tikz.contour)?;
+        writeln!(
+            w,
+            "  field line/.style={{{}, postaction={{decorate}}, \
+             decoration={{markings, mark=at position 0.5 with {{\\arrow{{Stealth}}}}}}}},",
+            tikz.field_line
+        )?;
+        writeln!(w, "  positive/.style={{{}}},", tikz.positive)?;
+        writeln!(w, "  negative/.style={{{}}},", tikz.negative)?;
+        writeln!(w, "  axis/.style={{{}}},", tikz.axis)?;
+        writeln!(w, "]")?;
+
+        writeln!(w, r"\begin{{scope}}")?;
+        writeln!(w, r"\clip (0,0) rectangle ({:.3},{:.3});", width, height)?;
+        if self.contours > 0 {
+            // about as many samples as the picture has points to draw
+            let (nx, ny) = (256, ((256.0 * height / width) as u32).max(2));
+            let samples = self.sample(figure, subject, nx, ny);
+            for line in self.contours(figure, &samples) {
+                let line = path(line.into_iter().map(to_cm), detail);
+                writeln!(w, r"\draw[contour] {};", line)?;
+            }
+        }
+        if let Some(streamlines) = &self.streamlines {
+            for line in streamlines.place(figure, subject) {
+                let line = path(line.into_iter().map(to_cm), detail);
+                writeln!(w, r"\draw[field line] {};", line)?;
+            }
+        }
+        writeln!(w, r"\end{{scope}}")?;
+
+        if self.axes {
+            let origin = Vec2::new(0f32.clamp(f.x_min, f.x_max), 0f32.clamp(f.y_min, f.y_max));
+            let o = to_cm(origin);
+            writeln!(
+                w,
+                r"\draw[axis, -{{Stealth}}] (0,{:.3}) -- ({:.3},{:.3}) node[right] {{$x$ / m}};",
+                o.y, width, o.y
+            )?;
+            writeln!(
+                w,
+                r"\draw[axis, -{{Stealth}}] ({:.3},0) -- ({:.3},{:.3}) node[above] {{$y$ / m}};",
+                o.x, o.x, height
+            )?;
+            for x in ticks(f.x_min, f.x_max, 8) {
+                let p = to_cm(Vec2::new(x, origin.y));
+                writeln!(
+                    w,
+                    r"\draw[axis] ({:.3},{:.3}) -- ++(0,-0.1) node[below] {{${}$}};",
+                    p.x,
+                    p.y,
+                    label(x)
+                )?;
+            }
+            for y in ticks(f.y_min, f.y_max, 8) {
+                // the x axis already marks where it crosses
+                if y == origin.y {
+                    continue;
+                }
+                let p = to_cm(Vec2::new(origin.x, y));
+                writeln!(
+                    w,
+                    r"\draw[axis] ({:.3},{:.3}) -- ++(-0.1,0) node[left] {{${}$}};",
+                    p.x,
+                    p.y,
+                    label(y)
+                )?;
+            }
+        }
+
+        if self.particles {
+            for p in subject.particles() {
+                let c = to_cm(p.pos.truncate());
+                let r = (p.radius * scale).max(0.1);
+                let (style, sign) = match p.value >= 0.0 {
+                    true => ("positive", "+"),
+                    false => ("negative", "-"),
+                };
+                writeln!(
+                    w,
+                    r"\filldraw[{}] ({:.3},{:.3}) circle[radius={:.3}] node[text=white] {{${}$}};",
+                    style, c.x, c.y, r, sign
+                )?;
+            }
+        }
+
+        writeln!(w, r"\end{{tikzpicture}}")?;
+        if tikz.standalone {
+            writeln!(w, r"\end{{document}}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn picture() {
+    let (particles, figure) = super::dipole();
+    let tikz = Tikz {
+        contour: "red".to_owned(),
+        ..Default::default()
+    };
+    let tex = Scene::default().tikz(&figure, &&particles[..], &tikz);
+    assert!(tex.starts_with(r"\documentclass[tikz]{standalone}"));
+    assert!(tex.contains("contour/.style={red},"));
+    assert!(tex.matches(r"\draw[contour]").count() > 5);
+    assert!(tex.matches(r"\draw[field line]").count() > 5);
+    // both particles, at either side of the 10 cm picture
+    assert!(tex.contains(
+        r"\filldraw[positive] (2.500,5.000) circle[radius=0.500] node[text=white] {$+$};"
+    ));
+    assert!(tex.contains(r"\filldraw[negative] (7.500,5.000)"));
+    assert!(tex.contains("node[below] {$-0.5$}"));
+
+    let tikz = Tikz {
+        standalone: false,
+        ..tikz
+    };
+    let tex = Scene::default().tikz(&figure, &&particles[..], &tikz);
+    assert!(tex.starts_with(r"\begin{tikzpicture}"));
+    assert_eq!(label(2e-5), r"2.00 \times 10^{-5}");
+}
@@ -0,0 +1,176 @@
+use glam::Vec2;
+
+use super::Figure;
+use crate::Particle;
+
+/// A [`Figure`] shown over `size` pixels of the screen, moved around like a camera.
+///
+/// Screen positions are measured from the top left corner of the view, with y pointing down.
+/// Every move changes the ranges of the figure, so whatever draws it follows along.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub figure: Figure,
+    pub size: Vec2,
+}
+
+impl Viewport {
+    /// The narrowest span of the world that can be zoomed in to, in metres.
+    pub const MIN_EXTENT: f32 = 1e-6;
+    /// The widest span of the world that can be zoomed out to, in metres.
+    pub const MAX_EXTENT: f32 = 1e6;
+
+    /// The figure's `width` and `height` are taken to be the size it's shown at.
+    pub fn new(mut figure: Figure, size: Vec2) -> Self {
+        let size = size.max(Vec2::ONE);
+        figure.width = size.x;
+        figure.height = size.y;
+        Self { figure, size }
+    }
+
+    /// The span of the view in the world.
+    fn extent(&self) -> Vec2 {
+        let f = &self.figure;
+        Vec2::new(f.x_max - f.x_min, f.y_max - f.y_min)
+    }
+
+    fn centre(&self) -> Vec2 {
+        let f = &self.figure;
+        0.5 * Vec2::new(f.x_min + f.x_max, f.y_min + f.y_max)
+    }
+
+    /// Shows `extent` of the world around `centre`.
+    fn set(&mut self, centre: Vec2, extent: Vec2) {
+        let half = 0.5 * extent;
+        self.figure.x_min = centre.x - half.x;
+        self.figure.x_max = centre.x + half.x;
+        self.figure.y_min = centre.y - half.y;
+        self.figure.y_max = centre.y + half.y;
+    }
+
+    pub fn to_screen(&self, world: Vec2) -> Vec2 {
+        self.figure.to_image(world, self.size)
+    }
+
+    pub fn to_world(&self, screen: Vec2) -> Vec2 {
+        self.figure.to_world(screen, self.size)
+    }
+
+    /// Moves the world along by `delta` on the screen, as if it were dragged.
+    pub fn pan(&mut self, delta: Vec2) {
+        let per_pixel = self.extent() / self.size;
+        let by = Vec2::new(-delta.x, delta.y) * per_pixel;
+        self.set(self.centre() + by, self.extent());
+    }
+
+    /// Zooms in by `factor`, or out when it's less than one,
+    /// keeping the point under `screen` where it is.
+    ///
+    /// The span of the view stays between [`Viewport::MIN_EXTENT`] and [`Viewport::MAX_EXTENT`],
+    /// and zooming stops short of where `f32` can no longer tell its edges apart.
+    pub fn zoom_at(&mut self, screen: Vec2, factor: f32) {
+        if !(factor.is_finite() && factor > 0.0) || factor == 1.0 {
+            return;
+        }
+        let extent = self.extent();
+        // a view already outside the limits can still zoom back towards them
+        let min = extent.min(Vec2::splat(Self::MIN_EXTENT));
+        let max = extent.max(Vec2::splat(Self::MAX_EXTENT));
+        let factor = extent / (extent / factor).clamp(min, max);
+        let fixed = self.to_world(screen);
+        let mut f = self.figure;
+        f.x_min = fixed.x + (f.x_min - fixed.x) / factor.x;
+        f.x_max = fixed.x + (f.x_max - fixed.x) / factor.x;
+        f.y_min = fixed.y + (f.y_min - fixed.y) / factor.y;
+        f.y_max = fixed.y + (f.y_max - fixed.y) / factor.y;
+        let usable = [f.x_min, f.x_max, f.y_min, f.y_max]
+            .iter()
+            .all(|x| x.is_finite())
+            && f.x_min < f.x_max
+            && f.y_min < f.y_max;
+        if usable {
+            self.figure = f;
+        }
+    }
+
+    /// Makes a unit as long along y as it is along x, so circles stay round.
+    ///
+    /// The view only ever grows to do so, keeping all of it in sight.
+    pub fn lock_aspect(&mut self) {
+        let per_pixel = self.extent() / self.size;
+        let scale = per_pixel.max_element();
+        // already close enough, so that doing this every frame settles
+        if (per_pixel.x - per_pixel.y).abs() <= 1e-4 * scale {
+            return;
+        }
+        self.set(self.centre(), self.size * scale);
+    }
+
+    /// Frames every particle with `margin` of the view to spare on each side.
+    /// Without any particles this goes back to the view of a new figure.
+    pub fn fit(&mut self, particles: &[Particle], margin: f32) {
+        if particles.is_empty() {
+            self.figure = Figure::new(self.size.x, self.size.y);
+            return;
+        }
+        let (mut min, mut max) = (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY));
+        for p in particles {
+            let pos = p.pos.truncate();
+            min = min.min(pos - p.radius);
+            max = max.max(pos + p.radius);
+        }
+        // a lone point still needs something around it
+        let extent = (max - min).max(Vec2::splat(1e-6));
+        self.set(0.5 * (min + max), extent * (1.0 + 2.0 * margin));
+    }
+}
+
+#[test]
+fn camera() {
+    let mut view = Viewport::new(Figure::new(0.0, 0.0), Vec2::new(200.0, 100.0));
+    assert_eq!(view.figure.width, 200.0);
+    let p = Vec2::new(0.25, -0.5);
+    assert!(view.to_world(view.to_screen(p)).distance(p) < 1e-6);
+    assert_eq!(view.to_screen(Vec2::new(-1.0, 1.0)), Vec2::ZERO);
+
+    // the point under the cursor stays put
+    let cursor = Vec2::new(150.0, 20.0);
+    let under = view.to_world(cursor);
+    view.zoom_at(cursor, 4.0);
+    assert!(view.to_world(cursor).distance(under) < 1e-6);
+    assert!((view.figure.x_max - view.figure.x_min - 0.5).abs() < 1e-6);
+
+    // however far it's zoomed, the view keeps a size that can be drawn
+    let mut far = view;
+    for _ in 0..1000 {
+        far.zoom_at(cursor, 0.5);
+    }
+    let extent = far.extent();
+    assert!(extent.is_finite() && extent.max_element() <= Viewport::MAX_EXTENT * 1.001);
+    for _ in 0..1000 {
+        far.zoom_at(cursor, 2.0);
+    }
+    let f = far.figure;
+    assert!(f.x_min < f.x_max && f.y_min < f.y_max);
+    // the edges round to the nearest f32, so the span lands only near the limit
+    assert!(far.extent().min_element() >= Viewport::MIN_EXTENT * 0.9);
+
+    // dragged right, the world follows
+    let before = view.to_world(Vec2::new(100.0, 50.0));
+    view.pan(Vec2::new(10.0, 0.0));
+    assert!(view.to_screen(before).distance(Vec2::new(110.0, 50.0)) < 1e-3);
+
+    view.lock_aspect();
+    let per_pixel = view.extent() / view.size;
+    assert!((per_pixel.x - per_pixel.y).abs() < 1e-6);
+    let locked = view.figure;
+    view.lock_aspect();
+    assert_eq!(view.figure, locked);
+
+    let particles = [
+        Particle::new(1.0, 0.5, Vec2::new(-3.0, 0.0)),
+        Particle::new(1.0, 0.5, Vec2::new(3.0, 1.0)),
+    ];
+    view.fit(&particles, 0.0);
+    assert_eq!(view.figure.x_min, -3.5);
+    assert_eq!(view.figure.y_max, 1.5);
+}
@@ -0,0 +1,321 @@
+//! Undo and redo for changes to the particles and figure.
+
+use crate::{animation::Track, graph::Figure, Particle};
+
+/// Everything a [`Command`] can change.
+pub struct Document<'a> {
+    pub particles: &'a mut Vec<Particle>,
+    /// How each particle is animated, kept alongside `particles`.
+    pub tracks: &'a mut Vec<Track>,
+    pub figure: &'a mut Figure,
+}
+
+/// A single change, which knows how to take itself back.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Add {
+        index: usize,
+        particle: Particle,
+        track: Track,
+    },
+    Remove {
+        index: usize,
+        particle: Particle,
+        track: Track,
+    },
+    /// The particle at `index` went from `old` to `new`.
+    Edit {
+        index: usize,
+        old: Particle,
+        new: Particle,
+    },
+    Figure {
+        old: Figure,
+        new: Figure,
+    },
+    /// Several commands as one step, such as a paste or moving a group.
+    Group {
+        name: String,
+        commands: Vec<Command>,
+    },
+}
+
+impl Command {
+    /// Removes the particle at `index`, along with its track.
+    pub fn remove(doc: &Document, index: usize) -> Self {
+        Command::Remove {
+            index,
+            particle: doc.particles[index],
+            track: doc.tracks.get(index).cloned().unwrap_or_default(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Command::Add { index, .. } => format!("Add particle {}", index + 1),
+            Command::Remove { index, .. } => format!("Remove particle {}", index + 1),
+            Command::Edit { index, old, new } => {
+                let moved = old.value == new.value && old.radius == new.radius;
+                let verb = if moved { "Move" } else { "Edit" };
+                format!("{} particle {}", verb, index + 1)
+            }
+            Command::Figure { .. } => "Change figure".to_owned(),
+            Command::Group { name, .. } => name.clone(),
+        }
+    }
+
+    pub fn apply(&self, doc: &mut Document) {
+        doc.tracks
+            .resize_with(doc.particles.len(), Default::default);
+        match self {
+            Command::Add {
+                index,
+                particle,
+                track,
+            } => {
+                doc.particles.insert(*index, *particle);
+                doc.tracks.insert(*index, track.clone());
+            }
+            Command::Remove { index, .. } => {
+                doc.particles.remove(*index);
+                doc.tracks.remove(*index);
+            }
+            Command::Edit { index, new, .. } => doc.particles[*index] = *new,
+            Command::Figure { new, .. } => *doc.figure = *new,
+            Command::Group { commands, .. } => {
+                for command in commands {
+                    command.apply(doc);
+                }
+            }
+        }
+    }
+
+    /// The command that takes this one back.
+    pub fn inverse(&self) -> Self {
+        match self.clone() {
+            Command::Add {
+                index,
+                particle,
+                track,
+            } => Command::Remove {
+                index,
+                particle,
+                track,
+            },
+            Command::Remove {
+                index,
+                particle,
+                track,
+            } => Command::Add {
+                index,
+                particle,
+                track,
+            },
+            Command::Edit { index, old, new } => Command::Edit {
+                index,
+                old: new,
+                new: old,
+            },
+            Command::Figure { old, new } => Command::Figure { old: new, new: old },
+            Command::Group { name, commands } => Command::Group {
+                name,
+                commands: commands.iter().rev().map(Command::inverse).collect(),
+            },
+        }
+    }
+
+    /// Folds `next` into this command if it carries on changing the same thing.
+    fn merge(&mut self, next: &Command) -> bool {
+        match (self, next) {
+            (
+                Command::Edit { index, new, .. },
+                Command::Edit {
+                    index: i, new: n, ..
+                },
+            ) if index == i => {
+                *new = *n;
+                true
+            }
+            (Command::Figure { new, .. }, Command::Figure { new: n, .. }) => {
+                *new = *n;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Doesn't change anything at all.
+    fn is_noop(&self) -> bool {
+        match self {
+            Command::Edit { old, new, .. } => old == new,
+            Command::Figure { old, new } => old == new,
+            Command::Group { commands, .. } => commands.iter().all(Command::is_noop),
+            _ => false,
+        }
+    }
+}
+
+/// The commands that have been done, and those that were undone and can be done again.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    done: Vec<Command>,
+    undone: Vec<Command>,
+    /// The last command can still take in more of the same change.
+    open: bool,
+}
+
+impl History {
+    /// How many steps are kept, the oldest are forgotten first.
+    const LIMIT: usize = 256;
+
+    /// Oldest first.
+    pub fn done(&self) -> &[Command] {
+        &self.done
+    }
+
+    /// Most recently undone last.
+    pub fn undone(&self) -> &[Command] {
+        &self.undone
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Makes a change, as a step of its own.
+    pub fn apply(&mut self, command: Command, doc: &mut Document) {
+        if command.is_noop() {
+            return;
+        }
+        command.apply(doc);
+        self.push(command);
+        self.open = false;
+    }
+
+    /// Records a change that has already been made, such as by a widget editing in place.
+    ///
+    /// Changes to the same thing are folded into one step until [`History::settle`],
+    /// so a whole drag can be undone at once.
+    pub fn record(&mut self, command: Command) {
+        if command.is_noop() {
+            return;
+        }
+        if self.open {
+            if let Some(last) = self.done.last_mut() {
+                if last.merge(&command) {
+                    if last.is_noop() {
+                        self.done.pop();
+                    }
+                    self.undone.clear();
+                    return;
+                }
+            }
+        }
+        self.push(command);
+        self.open = true;
+    }
+
+    /// Finishes the current step, the next change starts another.
+    pub fn settle(&mut self) {
+        self.open = false;
+    }
+
+    pub fn undo(&mut self, doc: &mut Document) -> bool {
+        self.open = false;
+        match self.done.pop() {
+            Some(command) => {
+                command.inverse().apply(doc);
+                self.undone.push(command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, doc: &mut Document) -> bool {
+        self.open = false;
+        match self.undone.pop() {
+            Some(command) => {
+                command.apply(doc);
+                self.done.push(command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Undoes or redoes until `done` steps are done.
+    pub fn go_to(&mut self, done: usize, doc: &mut Document) {
+        while self.done.len() > done && self.undo(doc) {}
+        while self.done.len() < done && self.redo(doc) {}
+    }
+
+    /// Forgets everything, for when the whole document is replaced.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push(&mut self, command: Command) {
+        self.undone.clear();
+        self.done.push(command);
+        if self.done.len() > Self::LIMIT {
+            self.done.remove(0);
+        }
+    }
+}
+
+#[test]
+fn undo_redo() {
+    use glam::Vec2;
+
+    let (mut particles, mut tracks) = (Vec::new(), Vec::new());
+    let mut figure = Figure::new(100.0, 100.0);
+    let mut doc = Document {
+        particles: &mut particles,
+        tracks: &mut tracks,
+        figure: &mut figure,
+    };
+    let mut history = History::default();
+    let p = Particle::new(1.0, 0.1, Vec2::ZERO);
+    history.apply(
+        Command::Add {
+            index: 0,
+            particle: p,
+            track: Track::default(),
+        },
+        &mut doc,
+    );
+
+    // a drag, one frame at a time
+    let mut moved = p;
+    for x in 1..=10 {
+        let old = moved;
+        moved.pos.x = x as f32 * 0.1;
+        doc.particles[0] = moved;
+        history.record(Command::Edit {
+            index: 0,
+            old,
+            new: moved,
+        });
+    }
+    history.settle();
+    assert_eq!(history.done().len(), 2);
+    assert_eq!(history.done()[1].name(), "Move particle 1");
+
+    assert!(history.undo(&mut doc));
+    assert_eq!(doc.particles[0], p);
+    assert!(history.undo(&mut doc));
+    assert!(doc.particles.is_empty() && doc.tracks.is_empty());
+    assert!(!history.undo(&mut doc));
+    assert!(history.redo(&mut doc) && history.redo(&mut doc));
+    assert_eq!(doc.particles[0], moved);
+
+    let remove = Command::remove(&doc, 0);
+    history.apply(remove, &mut doc);
+    assert!(doc.particles.is_empty());
+    assert!(!history.can_redo());
+}
@@ -1,9 +1,25 @@
+mod formula;
 mod particle;
+mod sheet;
+mod slice;
+mod transform;
 
+pub mod animation;
+pub mod edit;
+pub mod expr;
 pub mod graph;
+pub mod history;
+pub mod scenario;
 pub mod scientific;
+pub mod units;
 
+pub use potential_core::*;
+
+pub use formula::*;
 pub use particle::*;
+pub use sheet::*;
+pub use slice::*;
+pub use transform::*;
 
 use glam::Vec2;
 
@@ -19,4 +35,4 @@ pub fn map_pos(pos: Vec2, res: Vec2, x_axis: Vec2, y_axis: Vec2) -> Vec2 {
     let x = map(x, res.x, x_axis.x, x_axis.y);
     let y = map(y, res.y, y_axis.y, y_axis.x);
     Vec2::new(x, y)
-}
\ No newline at end of file
+}
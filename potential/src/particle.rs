@@ -1,106 +1,79 @@
-#![cfg_attr(target_arch = "spirv", no_std, feature(lang_items))]
+use glam::{Vec2, Vec3};
 
-use glam::Vec2;
+use crate::{
+    units::{Charge, ElectricField, ElectricPotential, Length},
+    Field, Force, Particle, Voltage, COULOMB,
+};
 
-// USEFUL CONSTANTS
-pub const COULOMB: f32 = 8.987_552e9;
-pub const GRAVITATIONAL: f32 = 6.674_302e-11;
+/// A [`Particle`] read in SI units.
+///
+/// These live out here because `potential-core` has no idea about units.
+pub trait ParticleUnits {
+    fn with_units(charge: Charge, radius: Length, pos: Vec3) -> Self;
 
-pub trait Field<T> {
-    fn at(&self, pos: Vec2) -> T;
-}
+    /// The value of the particle, read as a charge.
+    fn charge(&self) -> Charge;
 
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
-pub struct Distance(pub f32);
+    fn radius(&self) -> Length;
 
-impl<'a> Field<Distance> for &'a [Particle] {
-    fn at(&self, pos: Vec2) -> Distance {
-        let mut d = Distance(f32::INFINITY);
-        for p in *self {
-            d = Distance(p.dist(pos).0.min(d.0));
-        }
-        d
-    }
+    /// Like [`Particle::voltage`], but in volts rather than leaving out [`COULOMB`].
+    fn electric_potential(&self, pos: Vec2) -> ElectricPotential;
+
+    /// The potential all over the surface, in volts.
+    fn surface_potential(&self) -> ElectricPotential;
+
+    /// Strength of the field, in volts per metre rather than leaving out [`COULOMB`].
+    fn electric_field(&self, pos: Vec2) -> ElectricField;
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Potential(pub Vec2);
-
-impl<'a> Field<Potential> for &'a [Particle] {
-    fn at(&self, pos: Vec2) -> Potential {
-        let mut v = Potential(Vec2::ZERO);
-        for p in *self {
-            match p.potential(pos) {
-                Ok(x) => v.0 += x.0,
-                Err(x) => return x,
-            }
-        }
-        v
+impl ParticleUnits for Particle {
+    #[inline]
+    fn with_units(charge: Charge, radius: Length, pos: Vec3) -> Self {
+        Particle::new_3d(charge.0, radius.0, pos)
     }
-}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Force(pub Vec2);
-
-impl<'a> Field<Force> for &'a [Particle] {
-    fn at(&self, pos: Vec2) -> Force {
-        let mut e = Force(Vec2::ZERO);
-        for p in *self {
-            match p.force(pos) {
-                Some(x) => e.0 += x.0,
-                None => return Force(Vec2::ZERO),
-            }
-        }
-        e
+    #[inline]
+    fn charge(&self) -> Charge {
+        Charge(self.value)
     }
-}
 
-#[repr(C)]
-#[derive(Clone, Copy)]
-pub struct Particle {
-    pub value: f32,
-    pub radius: f32,
-    pub pos: Vec2,
-}
+    #[inline]
+    fn radius(&self) -> Length {
+        Length(self.radius)
+    }
 
-impl Default for Particle {
-    fn default() -> Self {
-        Self {
-            value: 1.0,
-            radius: 1.0,
-            pos: Default::default(),
-        }
+    fn electric_potential(&self, pos: Vec2) -> ElectricPotential {
+        ElectricPotential(COULOMB * self.voltage(pos).0)
     }
-}
 
-impl Particle {
-    #[inline]
-    pub fn new(value: f32, radius: f32, pos: Vec2) -> Self {
-        Self { value, radius, pos }
+    fn surface_potential(&self) -> ElectricPotential {
+        ElectricPotential(COULOMB * self.value / self.radius)
     }
 
-    pub fn dist(&self, pos: Vec2) -> Distance {
-        Distance((pos - self.pos).length() - self.radius)
+    fn electric_field(&self, pos: Vec2) -> ElectricField {
+        let e = self.force(pos).map_or(0.0, |Force(e)| e.length());
+        ElectricField(COULOMB * e)
     }
+}
+
+/// A [`Field`] of charges read in SI units, rather than leaving out [`COULOMB`].
+pub trait FieldUnits {
+    fn electric_potential(&self, pos: Vec2) -> ElectricPotential;
+
+    /// The x and y components of the field.
+    fn electric_field(&self, pos: Vec2) -> [ElectricField; 2];
+}
 
-    pub fn potential(&self, pos: Vec2) -> Result<Potential, Potential> {
-        let vec = pos - self.pos;
-        if self.dist(pos).0 >= 0.0 {
-            let r = vec.length();
-            Ok(Potential(vec * self.value / (r * r)))
-        } else {
-            let r = self.radius;
-            Err(Potential(vec * self.value / (r * r)))
-        }
+impl<F> FieldUnits for F
+where
+    F: Field<Voltage> + Field<Force>,
+{
+    fn electric_potential(&self, pos: Vec2) -> ElectricPotential {
+        ElectricPotential(COULOMB * Field::<Voltage>::at(self, pos).0)
     }
 
-    pub fn force(&self, pos: Vec2) -> Option<Force> {
-        let vec = pos - self.pos;
-        if self.dist(pos).0 >= 0.0 {
-            let r = vec.length();
-            Some(Force(vec * self.value / (r * r * r)))
-        } else {
-            None
-        }
+    fn electric_field(&self, pos: Vec2) -> [ElectricField; 2] {
+        let Force(e) = self.at(pos);
+        [ElectricField(COULOMB * e.x), ElectricField(COULOMB * e.y)]
     }
 }
@@ -0,0 +1,602 @@
+//! Scenarios saved as JSON, so that a setup can be shared and opened again.
+//! They can also be packed into a link, see [`Scenario::to_link`].
+//!
+//! Every file has a `version`, and older files are migrated forward when they are read:
+//!
+//! 1. Particles as `{ "value", "radius", "pos" }` and the figure as
+//!    `[x_min, x_max, y_min, y_max]`, nothing else.
+//! 2. Particles have a `charge` rather than a `value`,
+//!    the figure is split into `x` and `y` ranges,
+//!    and the geometry, formula and view settings are kept too.
+//! 3. The view keeps the [`Slice`] the figure is seen through,
+//!    since the figure's ranges are measured across it.
+
+use std::str::FromStr;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{
+    expr::ParseError,
+    graph::{Colormap, Figure, Quantity, Scale},
+    Formula, Geometry, Particle, Slice,
+};
+
+/// The version written by [`Scenario::to_json`].
+pub const VERSION: u32 = 3;
+
+#[derive(Debug, Error)]
+pub enum ScenarioError {
+    #[error("not a scenario: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the scenario has no version")]
+    NoVersion,
+    #[error("the scenario is version {0}, but only up to version {max} can be read", max = VERSION)]
+    Newer(u64),
+    #[error("the {0} isn't laid out the way a scenario's is")]
+    Shape(&'static str),
+    #[error("particle {} has a radius of {radius}, it needs to be positive", .index + 1)]
+    Radius { index: usize, radius: f32 },
+    #[error("particle {} has a value that isn't a finite number", .index + 1)]
+    NotFinite { index: usize },
+    #[error("the figure is empty along {axis}")]
+    EmptyFigure { axis: char },
+    #[error("the formula doesn't parse: {0}")]
+    Formula(#[from] ParseError),
+    #[error("the link isn't valid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("the link is cut short or garbled")]
+    Link,
+    #[error("the {0} is too long to fit in a link")]
+    TooLong(&'static str),
+}
+
+/// A whole setup: what the sources are, where they are and how they're viewed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub version: u32,
+    pub geometry: Geometry,
+    pub figure: Extent,
+    pub particles: Vec<Body>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula: Option<FormulaText>,
+    #[serde(default)]
+    pub view: View,
+}
+
+/// The part of the plane in view, `[min, max]` along each axis.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Extent {
+    pub x: [f32; 2],
+    pub y: [f32; 2],
+}
+
+impl Extent {
+    /// Moves `figure` over to this extent, keeping its size on screen.
+    pub fn apply(&self, figure: &mut Figure) {
+        let ([x_min, x_max], [y_min, y_max]) = (self.x, self.y);
+        figure.x_min = x_min;
+        figure.x_max = x_max;
+        figure.y_min = y_min;
+        figure.y_max = y_max;
+    }
+}
+
+impl From<&Figure> for Extent {
+    fn from(f: &Figure) -> Self {
+        Self {
+            x: [f.x_min, f.x_max],
+            y: [f.y_min, f.y_max],
+        }
+    }
+}
+
+/// A particle as it's saved.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Body {
+    pub charge: f32,
+    pub radius: f32,
+    pub pos: [f32; 3],
+}
+
+impl From<&Particle> for Body {
+    fn from(p: &Particle) -> Self {
+        Self {
+            charge: p.value,
+            radius: p.radius,
+            pos: p.pos.to_array(),
+        }
+    }
+}
+
+impl From<&Body> for Particle {
+    fn from(b: &Body) -> Self {
+        Particle {
+            value: b.charge,
+            radius: b.radius,
+            pos: Vec3::from(b.pos),
+        }
+    }
+}
+
+/// A [`Formula`] as it was typed, along with the values of its parameters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FormulaText {
+    pub text: String,
+    #[serde(default)]
+    pub params: Vec<(String, f32)>,
+}
+
+impl FormulaText {
+    pub fn formula(&self) -> Result<Formula, ParseError> {
+        let mut formula: Formula = self.text.parse()?;
+        for (name, value) in &mut formula.params {
+            if let Some((_, v)) = self.params.iter().find(|(n, _)| n == name) {
+                *value = *v;
+            }
+        }
+        Ok(formula)
+    }
+}
+
+/// How the graph is drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub quantity: Quantity,
+    pub colormap: Colormap,
+    pub scale: Scale,
+    /// Draw the line integral convolution rather than the heatmap.
+    pub lic: bool,
+    pub arrows: bool,
+    pub field_lines: bool,
+    pub slice: Slice,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            quantity: Quantity::Voltage,
+            colormap: Colormap::Viridis,
+            scale: Scale::Linear,
+            lic: false,
+            arrows: false,
+            field_lines: false,
+            slice: Slice::default(),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn new(geometry: Geometry, figure: &Figure, particles: &[Particle]) -> Self {
+        Self {
+            version: VERSION,
+            geometry,
+            figure: figure.into(),
+            particles: particles.iter().map(Body::from).collect(),
+            formula: None,
+            view: View::default(),
+        }
+    }
+
+    pub fn particles(&self) -> Vec<Particle> {
+        self.particles.iter().map(Particle::from).collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        // only plain structs, numbers and strings, which always serialize
+        serde_json::to_string_pretty(self).expect("scenarios serialize")
+    }
+
+    /// Checks that everything in the scenario makes sense to draw.
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        for (axis, [min, max]) in [('x', self.figure.x), ('y', self.figure.y)] {
+            if !(min.is_finite() && max.is_finite() && min < max) {
+                return Err(ScenarioError::EmptyFigure { axis });
+            }
+        }
+        for (index, b) in self.particles.iter().enumerate() {
+            if !(b.charge.is_finite()
+                && b.radius.is_finite()
+                && b.pos.iter().all(|x| x.is_finite()))
+            {
+                return Err(ScenarioError::NotFinite { index });
+            }
+            if b.radius <= 0.0 {
+                return Err(ScenarioError::Radius {
+                    index,
+                    radius: b.radius,
+                });
+            }
+        }
+        if let Some(formula) = &self.formula {
+            formula.formula()?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = ScenarioError;
+
+    /// Reads a scenario of any version, migrating it to the current one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = migrate(serde_json::from_str(s)?)?;
+        let scenario: Scenario = serde_json::from_value(value)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+}
+
+/// Enums in a link are stored as their index in these.
+const GEOMETRIES: [Geometry; 3] = [Geometry::Points, Geometry::Sheets, Geometry::Formula];
+const QUANTITIES: [Quantity; 2] = [Quantity::Voltage, Quantity::Field];
+
+fn index<T: PartialEq>(all: &[T], x: T) -> u8 {
+    all.iter().position(|a| *a == x).unwrap_or(0) as u8
+}
+
+impl Scenario {
+    /// The scenario packed into as few bytes as it'll go,
+    /// as URL safe base64 to go in the fragment of a link.
+    ///
+    /// Links hold the current version, but older ones can still be read,
+    /// see [`Scenario::from_link`].
+    pub fn to_link(&self) -> Result<String, ScenarioError> {
+        let mut w = Vec::new();
+        let f32 = |w: &mut Vec<u8>, x: f32| w.extend_from_slice(&x.to_le_bytes());
+        // lengths take two bytes
+        let len = |w: &mut Vec<u8>, n: usize, what| -> Result<(), ScenarioError> {
+            let n = u16::try_from(n).map_err(|_| ScenarioError::TooLong(what))?;
+            w.extend_from_slice(&n.to_le_bytes());
+            Ok(())
+        };
+        let str = |w: &mut Vec<u8>, s: &str, what| -> Result<(), ScenarioError> {
+            len(w, s.len(), what)?;
+            w.extend_from_slice(s.as_bytes());
+            Ok(())
+        };
+
+        w.push(VERSION as u8);
+        w.push(index(&GEOMETRIES, self.geometry));
+        for &x in self.figure.x.iter().chain(&self.figure.y) {
+            f32(&mut w, x);
+        }
+
+        let view = &self.view;
+        w.push(index(&QUANTITIES, view.quantity));
+        w.push(index(&Colormap::ALL, view.colormap));
+        match view.scale {
+            Scale::Linear => w.push(0),
+            Scale::Log => w.push(1),
+            Scale::Symlog { threshold } => {
+                w.push(2);
+                f32(&mut w, threshold);
+            }
+        }
+        w.push(view.lic as u8 | (view.arrows as u8) << 1 | (view.field_lines as u8) << 2);
+        for x in [view.slice.offset, view.slice.yaw, view.slice.pitch] {
+            f32(&mut w, x);
+        }
+
+        len(&mut w, self.particles.len(), "list of particles")?;
+        for b in &self.particles {
+            for x in [b.charge, b.radius, b.pos[0], b.pos[1], b.pos[2]] {
+                f32(&mut w, x);
+            }
+        }
+
+        match &self.formula {
+            None => w.push(0),
+            Some(formula) => {
+                w.push(1);
+                str(&mut w, &formula.text, "formula")?;
+                len(&mut w, formula.params.len(), "list of parameters")?;
+                for (name, value) in &formula.params {
+                    str(&mut w, name, "name of a parameter")?;
+                    f32(&mut w, *value);
+                }
+            }
+        }
+
+        Ok(base64::encode_config(&w, base64::URL_SAFE_NO_PAD))
+    }
+
+    /// Reads a scenario back out of [`Scenario::to_link`].
+    ///
+    /// Links started out at version 2, which has no [`Slice`];
+    /// those are seen through the default one, as files are when migrated.
+    pub fn from_link(link: &str) -> Result<Self, ScenarioError> {
+        let bytes = base64::decode_config(link, base64::URL_SAFE_NO_PAD)?;
+        let mut r = Reader(&bytes);
+
+        let version = r.u8()? as u32;
+        if version > VERSION {
+            return Err(ScenarioError::Newer(version as u64));
+        } else if version < 2 {
+            return Err(ScenarioError::Link);
+        }
+        let geometry = r.pick(&GEOMETRIES)?;
+        let figure = Extent {
+            x: [r.f32()?, r.f32()?],
+            y: [r.f32()?, r.f32()?],
+        };
+
+        let quantity = r.pick(&QUANTITIES)?;
+        let colormap = r.pick(&Colormap::ALL)?;
+        let scale = match r.u8()? {
+            0 => Scale::Linear,
+            1 => Scale::Log,
+            2 => Scale::Symlog {
+                threshold: r.f32()?,
+            },
+            _ => return Err(ScenarioError::Link),
+        };
+        let flags = r.u8()?;
+        let slice = match version {
+            2 => Slice::default(),
+            _ => Slice {
+                offset: r.f32()?,
+                yaw: r.f32()?,
+                pitch: r.f32()?,
+            },
+        };
+        let view = View {
+            quantity,
+            colormap,
+            scale,
+            lic: flags & 1 != 0,
+            arrows: flags & 2 != 0,
+            field_lines: flags & 4 != 0,
+            slice,
+        };
+
+        let particles = (0..r.u16()?)
+            .map(|_| {
+                Ok(Body {
+                    charge: r.f32()?,
+                    radius: r.f32()?,
+                    pos: [r.f32()?, r.f32()?, r.f32()?],
+                })
+            })
+            .collect::<Result<_, ScenarioError>>()?;
+
+        let formula = match r.u8()? {
+            0 => None,
+            _ => {
+                let text = r.str()?;
+                let params = (0..r.u16()?)
+                    .map(|_| Ok((r.str()?, r.f32()?)))
+                    .collect::<Result<_, ScenarioError>>()?;
+                Some(FormulaText { text, params })
+            }
+        };
+
+        let scenario = Scenario {
+            version: VERSION,
+            geometry,
+            figure,
+            particles,
+            formula,
+            view,
+        };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+}
+
+/// The bytes of a link, read from the front.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ScenarioError> {
+        if self.0.len() < n {
+            return Err(ScenarioError::Link);
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, ScenarioError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ScenarioError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, ScenarioError> {
+        let b = self.take(4)?;
+        Ok(f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn str(&mut self) -> Result<String, ScenarioError> {
+        let n = self.u16()? as usize;
+        String::from_utf8(self.take(n)?.to_vec()).map_err(|_| ScenarioError::Link)
+    }
+
+    /// One of `all`, by its index.
+    fn pick<T: Copy>(&mut self, all: &[T]) -> Result<T, ScenarioError> {
+        let i = self.u8()? as usize;
+        all.get(i).copied().ok_or(ScenarioError::Link)
+    }
+}
+
+/// Moves a scenario up a version, failing if it isn't laid out as that version's are.
+type Migration = fn(&mut Value) -> Result<(), ScenarioError>;
+
+/// Steps from each version to the next, the first going from version 1 to 2.
+const MIGRATIONS: [Migration; (VERSION - 1) as usize] = [v1_to_v2, v2_to_v3];
+
+fn migrate(mut value: Value) -> Result<Value, ScenarioError> {
+    let version = match value.get("version").and_then(Value::as_u64) {
+        Some(v) if v >= 1 => v,
+        _ => return Err(ScenarioError::NoVersion),
+    };
+    if version > VERSION as u64 {
+        return Err(ScenarioError::Newer(version));
+    }
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(&mut value)?;
+    }
+    value["version"] = json!(VERSION);
+    Ok(value)
+}
+
+fn v1_to_v2(value: &mut Value) -> Result<(), ScenarioError> {
+    let value = value.as_object_mut().ok_or(ScenarioError::Shape("file"))?;
+    if let Some(particles) = value.get_mut("particles").and_then(Value::as_array_mut) {
+        for p in particles.iter_mut().filter_map(Value::as_object_mut) {
+            if let Some(charge) = p.remove("value") {
+                p.insert("charge".to_owned(), charge);
+            }
+        }
+    }
+    if let Some(f) = value.get("figure").and_then(Value::as_array) {
+        if f.len() == 4 {
+            let figure = json!({ "x": [f[0], f[1]], "y": [f[2], f[3]] });
+            value.insert("figure".to_owned(), figure);
+        }
+    }
+    // there was nothing but particles
+    value.insert("geometry".to_owned(), json!(Geometry::Points));
+    Ok(())
+}
+
+fn v2_to_v3(value: &mut Value) -> Result<(), ScenarioError> {
+    let value = value.as_object_mut().ok_or(ScenarioError::Shape("file"))?;
+    // everything was seen through the plane the particles start in
+    if let Some(view) = value.get_mut("view") {
+        let view = view.as_object_mut().ok_or(ScenarioError::Shape("view"))?;
+        view.insert("slice".to_owned(), json!(Slice::default()));
+    }
+    Ok(())
+}
+
+#[test]
+fn round_trip() {
+    let particles = [
+        Particle::new(1.0, 0.1, glam::Vec2::new(-0.5, 0.0)),
+        Particle::new(-2.0, 0.2, glam::Vec2::new(0.5, 0.25)),
+    ];
+    let mut scenario = Scenario::new(Geometry::Formula, &Figure::new(100.0, 100.0), &particles);
+    scenario.formula = Some(FormulaText {
+        text: "a * x^2".to_owned(),
+        params: vec![("a".to_owned(), 3.0)],
+    });
+    scenario.view.scale = Scale::Symlog { threshold: 0.5 };
+    scenario.view.slice.pitch = 0.5;
+
+    let read: Scenario = scenario.to_json().parse().unwrap();
+    assert_eq!(read, scenario);
+    assert_eq!(read.particles(), particles);
+    let formula = read.formula.unwrap().formula().unwrap();
+    assert_eq!(formula.params, [("a".to_owned(), 3.0)]);
+}
+
+#[test]
+fn migrations() {
+    let v1 = r#"{
+        "version": 1,
+        "figure": [-2, 2, -1, 1],
+        "particles": [{ "value": 1.5, "radius": 0.1, "pos": [0, 1, 0] }]
+    }"#;
+    let scenario: Scenario = v1.parse().unwrap();
+    assert_eq!(scenario.version, VERSION);
+    assert_eq!(scenario.geometry, Geometry::Points);
+    assert_eq!(scenario.figure.x, [-2.0, 2.0]);
+    assert_eq!(scenario.particles[0].charge, 1.5);
+    assert_eq!(scenario.view, View::default());
+
+    let v2 = r#"{
+        "version": 2,
+        "geometry": "Points",
+        "figure": { "x": [-2, 2], "y": [-1, 1] },
+        "particles": [],
+        "view": { "arrows": true }
+    }"#;
+    let scenario: Scenario = v2.parse().unwrap();
+    assert!(scenario.view.arrows);
+    assert_eq!(scenario.view.slice, Slice::default());
+
+    let err = |s: &str| s.parse::<Scenario>().unwrap_err();
+    assert!(matches!(err("{}"), ScenarioError::NoVersion));
+    assert!(matches!(
+        err(r#"{ "version": 9 }"#),
+        ScenarioError::Newer(9)
+    ));
+    assert!(matches!(err("[1, 2"), ScenarioError::Json(_)));
+    let bad = v2.replace(r#"{ "arrows": true }"#, "3");
+    assert_eq!(
+        err(&bad).to_string(),
+        "the view isn't laid out the way a scenario's is"
+    );
+    let bad = v1.replace("0.1", "-0.1");
+    assert_eq!(
+        err(&bad).to_string(),
+        "particle 1 has a radius of -0.1, it needs to be positive"
+    );
+    let bad = v1.replace("-1, 1", "1, 1");
+    assert!(matches!(
+        err(&bad),
+        ScenarioError::EmptyFigure { axis: 'y' }
+    ));
+}
+
+#[test]
+fn links() {
+    let particles = [
+        Particle::new(1.0, 0.1, glam::Vec2::new(-0.5, 0.0)),
+        Particle::new(-2.0, 0.2, glam::Vec2::new(0.5, 0.25)),
+    ];
+    let mut scenario = Scenario::new(Geometry::Points, &Figure::new(100.0, 100.0), &particles);
+    scenario.formula = Some(FormulaText {
+        text: "a / x".to_owned(),
+        params: vec![("a".to_owned(), 2.0)],
+    });
+    scenario.view.scale = Scale::Symlog { threshold: 0.5 };
+    scenario.view.field_lines = true;
+    scenario.view.slice = Slice {
+        offset: 0.25,
+        yaw: 1.0,
+        pitch: -0.5,
+    };
+
+    let link = scenario.to_link().unwrap();
+    assert!(link
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    // much shorter than the JSON
+    assert!(link.len() * 4 < scenario.to_json().len());
+    assert_eq!(Scenario::from_link(&link).unwrap(), scenario);
+
+    // the same scenario as a version 2 link, from before the slice was kept
+    let mut bytes = base64::decode_config(&link, base64::URL_SAFE_NO_PAD).unwrap();
+    bytes[0] = 2;
+    // version, geometry, figure, quantity, colormap, scale and its threshold, flags
+    let slice_at = 1 + 1 + 16 + 1 + 1 + 1 + 4 + 1;
+    bytes.drain(slice_at..slice_at + 12);
+    let old = base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD);
+    let read = Scenario::from_link(&old).unwrap();
+    assert_eq!(read.view.slice, Slice::default());
+    assert_eq!(read.particles, scenario.particles);
+    assert_eq!(read.formula, scenario.formula);
+
+    let mut long = scenario.clone();
+    long.formula.as_mut().unwrap().text = "x + ".repeat(20_000) + "1";
+    assert_eq!(
+        long.to_link().unwrap_err().to_string(),
+        "the formula is too long to fit in a link"
+    );
+
+    let cut = &link[..link.len() - 8];
+    assert!(matches!(Scenario::from_link(cut), Err(ScenarioError::Link)));
+    assert!(matches!(
+        Scenario::from_link("not a link!"),
+        Err(ScenarioError::Base64(_))
+    ));
+}
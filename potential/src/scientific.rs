@@ -1,6 +1,8 @@
 pub struct Sci(pub f32);
 
-use core::fmt;
+use core::{fmt, str::FromStr};
+
+use thiserror::Error;
 
 impl fmt::Debug for Sci {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -26,3 +28,357 @@ impl fmt::Display for Sci {
         fmt::Debug::fmt(self, f)
     }
 }
+
+/// The SI prefixes from femto to tera, in steps of a thousand.
+pub const PREFIXES: [(i32, &str); 10] = [
+    (-15, "f"),
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "µ"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+];
+
+/// Splits `x` into a number in `[1, 1000)` and the SI prefix that goes with it,
+/// if there is one that fits.
+pub fn si_prefix(x: f32) -> Option<(f32, &'static str)> {
+    if x == 0.0 {
+        return Some((x, ""));
+    }
+    let exponent = x.abs().log10().floor() as i32;
+    PREFIXES
+        .iter()
+        .find(|(p, _)| (*p..*p + 3).contains(&exponent))
+        .map(|&(p, prefix)| (x / 10f32.powi(p), prefix))
+}
+
+/// Where the exponent goes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Notation {
+    /// One digit before the point.
+    Scientific,
+    /// Exponents that are a multiple of three.
+    Engineering,
+    /// Like engineering, with the exponent as a prefix such as `µ`.
+    /// Anything beyond the prefixes falls back on engineering.
+    Si,
+}
+
+/// How the exponent is written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Exponent {
+    /// `1.5e-3`
+    E,
+    /// `1.5×10⁻³`
+    Superscript,
+    /// `1.5 \times 10^{-3}`
+    Latex,
+}
+
+/// Options for writing a [`Sci`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Format {
+    pub sig_figs: u8,
+    pub notation: Notation,
+    pub exponent: Exponent,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            sig_figs: 3,
+            notation: Notation::Scientific,
+            exponent: Exponent::E,
+        }
+    }
+}
+
+impl Format {
+    pub fn sig_figs(self, sig_figs: u8) -> Self {
+        Self { sig_figs, ..self }
+    }
+
+    pub fn notation(self, notation: Notation) -> Self {
+        Self { notation, ..self }
+    }
+
+    pub fn exponent(self, exponent: Exponent) -> Self {
+        Self { exponent, ..self }
+    }
+}
+
+/// A [`Sci`] with the [`Format`] to write it in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Formatted {
+    value: f32,
+    format: Format,
+}
+
+impl Sci {
+    pub fn format(self, format: Format) -> Formatted {
+        Formatted {
+            value: self.0,
+            format,
+        }
+    }
+}
+
+impl fmt::Display for Formatted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Format {
+            sig_figs,
+            notation,
+            exponent: style,
+        } = self.format;
+        let x = self.value as f64;
+        if !x.is_finite() {
+            return write!(f, "{}", x);
+        }
+        let sig_figs = sig_figs.max(1) as i32;
+
+        // round to the significant figures first, as that can carry into the next power
+        let mut exponent = if x == 0.0 {
+            0
+        } else {
+            x.abs().log10().floor() as i32
+        };
+        let mut mantissa = round(x / 10f64.powi(exponent), sig_figs - 1);
+        if mantissa.abs() >= 10.0 {
+            exponent += 1;
+            mantissa /= 10.0;
+        }
+
+        let shift = match notation {
+            Notation::Scientific => 0,
+            Notation::Engineering | Notation::Si => exponent.rem_euclid(3),
+        };
+        let decimals = (sig_figs - 1 - shift).max(0) as usize;
+        let mantissa = mantissa * 10f64.powi(shift);
+        let exponent = exponent - shift;
+
+        if notation == Notation::Si {
+            if let Some((_, prefix)) = PREFIXES.iter().find(|(p, _)| *p == exponent) {
+                return match (style, *prefix) {
+                    (_, "") => write!(f, "{:.*}", decimals, mantissa),
+                    (Exponent::Latex, "µ") => write!(f, "{:.*}\\,\\mu", decimals, mantissa),
+                    (Exponent::Latex, p) => {
+                        write!(f, "{:.*}\\,\\mathrm{{{}}}", decimals, mantissa, p)
+                    }
+                    (_, p) => write!(f, "{:.*} {}", decimals, mantissa, p),
+                };
+            }
+        }
+
+        write!(f, "{:.*}", decimals, mantissa)?;
+        match style {
+            Exponent::E => write!(f, "e{}", exponent),
+            _ if exponent == 0 => Ok(()),
+            Exponent::Superscript => {
+                write!(f, "×10")?;
+                for c in exponent.to_string().chars() {
+                    write!(f, "{}", superscript(c))?;
+                }
+                Ok(())
+            }
+            Exponent::Latex => write!(f, " \\times 10^{{{}}}", exponent),
+        }
+    }
+}
+
+/// Rounds to a number of decimal places.
+fn round(x: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (x * scale).round() / scale
+}
+
+const SUPERSCRIPTS: [(char, char); 11] = [
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('-', '⁻'),
+];
+
+fn superscript(c: char) -> char {
+    SUPERSCRIPTS
+        .iter()
+        .find(|(n, _)| *n == c)
+        .map_or(c, |(_, s)| *s)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseSciError {
+    #[error("`{0}` doesn't start with a number")]
+    Number(String),
+    #[error("invalid exponent `{0}`")]
+    Exponent(String),
+    #[error("unknown prefix `{0}`")]
+    Prefix(String),
+}
+
+/// Reads back any of `3e-6`, `3×10^-6`, `3×10⁻⁶`, `3 \times 10^{-6}` or `3 µ`,
+/// so everything [`Formatted`] writes.
+impl FromStr for Sci {
+    type Err = ParseSciError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let end = number_len(s);
+        let mantissa: f32 = s[..end]
+            .parse()
+            .map_err(|_| ParseSciError::Number(s.to_owned()))?;
+        let rest = s[end..].trim_start();
+        if rest.is_empty() {
+            return Ok(Sci(mantissa));
+        }
+
+        let times = ["×", "x", "*", "\\times", "\\cdot"]
+            .iter()
+            .find_map(|t| rest.strip_prefix(t))
+            .and_then(|r| r.trim_start().strip_prefix("10"));
+        if let Some(exponent) = times {
+            let exponent = parse_exponent(exponent.trim())
+                .ok_or_else(|| ParseSciError::Exponent(exponent.trim().to_owned()))?;
+            return Ok(Sci(mantissa * 10f32.powi(exponent)));
+        }
+
+        let prefix = rest.strip_prefix("\\,").unwrap_or(rest);
+        let prefix = match prefix.strip_prefix("\\mathrm{") {
+            Some(p) => p.strip_suffix('}').unwrap_or(p),
+            None => prefix,
+        };
+        let prefix = match prefix {
+            // the Greek letter, LaTeX and ASCII stand-ins for the micro sign
+            "μ" | "\\mu" | "u" => "µ",
+            p => p,
+        };
+        PREFIXES
+            .iter()
+            .find(|(_, p)| *p == prefix)
+            .map(|(power, _)| Sci(mantissa * 10f32.powi(*power)))
+            .ok_or_else(|| ParseSciError::Prefix(rest.to_owned()))
+    }
+}
+
+/// The length of the number at the start of `s`, such as `-1.5e3`.
+fn number_len(s: &str) -> usize {
+    let b = s.as_bytes();
+    let mut i = 0;
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < b.len() && b[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i > start
+    };
+    if matches!(b.first(), Some(b'+' | b'-')) {
+        i += 1;
+    }
+    digits(&mut i);
+    if b.get(i) == Some(&b'.') {
+        i += 1;
+        digits(&mut i);
+    }
+    if matches!(b.get(i), Some(b'e' | b'E')) {
+        let mark = i;
+        i += 1;
+        if matches!(b.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !digits(&mut i) {
+            i = mark;
+        }
+    }
+    i
+}
+
+/// `^-6`, `^{-6}` or `⁻⁶`.
+fn parse_exponent(s: &str) -> Option<i32> {
+    match s.strip_prefix('^') {
+        Some(e) => {
+            let e = e.trim();
+            let e = match e.strip_prefix('{') {
+                Some(e) => e.strip_suffix('}')?,
+                None => e,
+            };
+            e.trim().parse().ok()
+        }
+        None => {
+            let plain: Option<String> = s
+                .chars()
+                .map(|c| SUPERSCRIPTS.iter().find(|(_, s)| *s == c).map(|(n, _)| *n))
+                .collect();
+            plain?.parse().ok()
+        }
+    }
+}
+
+#[test]
+fn format() {
+    let f = |x: f32, format: Format| Sci(x).format(format).to_string();
+    let default = Format::default();
+    assert_eq!(f(0.001234, default), "1.23e-3");
+    assert_eq!(f(999.96, default), "1.00e3");
+    assert_eq!(f(0.0, default.sig_figs(2)), "0.0e0");
+    assert_eq!(
+        f(-12345.0, default.notation(Notation::Engineering)),
+        "-12.3e3"
+    );
+    assert_eq!(f(0.00025, default.notation(Notation::Si)), "250 µ");
+    assert_eq!(f(4.5, default.notation(Notation::Si)), "4.50");
+    assert_eq!(f(3e20, default.notation(Notation::Si)), "300e18");
+    let superscript = default.exponent(Exponent::Superscript);
+    assert_eq!(f(-0.001, superscript), "-1.00×10⁻³");
+    assert_eq!(f(1.0, superscript), "1.00");
+    assert_eq!(
+        f(6.02e23, default.exponent(Exponent::Latex)),
+        "6.02 \\times 10^{23}"
+    );
+    // the original format is left alone
+    assert_eq!(Sci(0.001234).to_string(), "+1.23e-03");
+}
+
+#[test]
+fn parse() {
+    let parse = |s: &str| s.parse::<Sci>().map(|x| x.0);
+    for s in [
+        "3e-6",
+        "3E-6",
+        "3×10^-6",
+        "3 x 10^{-6}",
+        "3×10⁻⁶",
+        "3 \\times 10^{-6}",
+        "3 µ",
+        "3u",
+    ] {
+        assert!((parse(s).unwrap() - 3e-6).abs() < 1e-12, "{}", s);
+    }
+    assert_eq!(parse(" -2.5k"), Ok(-2500.0));
+    assert_eq!(parse("12"), Ok(12.0));
+    assert_eq!(parse("k"), Err(ParseSciError::Number("k".to_owned())));
+    assert_eq!(parse("3 q"), Err(ParseSciError::Prefix("q".to_owned())));
+    assert_eq!(parse("3×10^"), Err(ParseSciError::Exponent("^".to_owned())));
+
+    // what's written can be read back
+    let formats = [Notation::Scientific, Notation::Engineering, Notation::Si].map(|n| {
+        [Exponent::E, Exponent::Superscript, Exponent::Latex]
+            .map(|e| Format::default().sig_figs(6).notation(n).exponent(e))
+    });
+    for format in formats.iter().flatten() {
+        for x in [-4.2e-7, 0.0, 1.0, 123456.0, 7.5e15] {
+            let s = Sci(x).format(*format).to_string();
+            let back = parse(&s).unwrap();
+            assert!((back - x).abs() <= 1e-5 * x.abs(), "{} {}", s, back);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use core::f32::consts::PI;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{Field, Force, Particle, Voltage};
+
+/// How the sources in a scene are shaped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Geometry {
+    /// Charged spheres, viewed through the plane they sit in.
+    Points,
+    /// Infinite planes of surface charge perpendicular to the x axis,
+    /// so everything only depends on x.
+    Sheets,
+    /// No sources at all, just a potential typed in as a [`Formula`](crate::Formula).
+    Formula,
+}
+
+/// Particles read as infinite sheets at `pos.x` with surface charge `value`.
+///
+/// The field of each sheet is `2πσ` pointing away from it on either side,
+/// so the potential is piecewise linear and the field piecewise constant.
+#[derive(Copy, Clone, Debug)]
+pub struct Sheets<'a>(pub &'a [Particle]);
+
+impl Field<Voltage> for Sheets<'_> {
+    fn at(&self, pos: Vec2) -> Voltage {
+        let mut v = Voltage(0.0);
+        for p in self.0 {
+            v.0 -= 2.0 * PI * p.value * (pos.x - p.pos.x).abs();
+        }
+        v
+    }
+}
+
+impl Field<Force> for Sheets<'_> {
+    fn at(&self, pos: Vec2) -> Force {
+        let mut e = Force(Vec2::ZERO);
+        for p in self.0 {
+            // on the sheet itself both sides cancel out
+            let dx = pos.x - p.pos.x;
+            if dx != 0.0 {
+                e.0.x += 2.0 * PI * p.value * dx.signum();
+            }
+        }
+        e
+    }
+}
+
+#[test]
+fn capacitor() {
+    // equal and opposite sheets only have a field between them
+    let sheets = [
+        Particle::new(1.0, 0.0, Vec2::new(-1.0, 0.0)),
+        Particle::new(-1.0, 0.0, Vec2::new(1.0, 0.0)),
+    ];
+    let field = Sheets(&sheets);
+    let e = |x: f32| Field::<Force>::at(&field, Vec2::new(x, 3.0)).0;
+    assert_eq!(e(-2.0), Vec2::ZERO);
+    assert_eq!(e(2.0), Vec2::ZERO);
+    assert!((e(0.0).x - 4.0 * PI).abs() < 1e-5);
+
+    let v = |x: f32| Field::<Voltage>::at(&field, Vec2::new(x, -2.0)).0;
+    // flat outside, dropping linearly between the plates
+    assert!((v(-3.0) - v(-2.0)).abs() < 1e-5);
+    assert!((v(-1.0) - v(1.0) - 8.0 * PI).abs() < 1e-4);
+    assert!((v(0.0) - 0.5 * (v(-1.0) + v(1.0))).abs() < 1e-4);
+}
@@ -0,0 +1,131 @@
+use glam::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::Particle;
+
+/// A plane through space that the figure is viewed through.
+///
+/// With no rotation or offset this is the `z = 0` plane,
+/// with x and y lining up with the figure.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Slice {
+    /// Distance of the plane from the origin, along its normal.
+    pub offset: f32,
+    /// Rotation about the z axis, in radians.
+    pub yaw: f32,
+    /// Tilt about the in-plane x axis, in radians.
+    pub pitch: f32,
+}
+
+impl Default for Slice {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+impl Slice {
+    #[inline]
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_z(self.yaw) * Quat::from_rotation_x(self.pitch)
+    }
+
+    /// The in-plane x and y axes, followed by the normal.
+    pub fn basis(&self) -> [Vec3; 3] {
+        let rot = self.rotation();
+        [rot * Vec3::X, rot * Vec3::Y, rot * Vec3::Z]
+    }
+
+    /// Where a point of the figure sits in space.
+    pub fn to_space(&self, pos: Vec2) -> Vec3 {
+        let [u, v, n] = self.basis();
+        n * self.offset + u * pos.x + v * pos.y
+    }
+
+    /// A point in space relative to the plane,
+    /// in-plane coordinates in x and y and the height above it in z.
+    pub fn to_local(&self, pos: Vec3) -> Vec3 {
+        let [u, v, n] = self.basis();
+        let d = pos - n * self.offset;
+        Vec3::new(d.dot(u), d.dot(v), d.dot(n))
+    }
+
+    /// The inverse of [`Slice::to_local`].
+    pub fn from_local(&self, local: Vec3) -> Vec3 {
+        let [.., n] = self.basis();
+        self.to_space(local.truncate()) + n * local.z
+    }
+
+    /// The particles as seen from the plane.
+    ///
+    /// The plane becomes `z = 0`, so every field of the result
+    /// gives the potential on the plane and the field lying in it.
+    pub fn local(&self, particles: &[Particle]) -> Vec<Particle> {
+        particles
+            .iter()
+            .map(|p| Particle {
+                pos: self.to_local(p.pos),
+                ..*p
+            })
+            .collect()
+    }
+
+    /// The inverse of [`Slice::local`], putting the particles back in space.
+    pub fn world(&self, particles: &[Particle]) -> Vec<Particle> {
+        particles
+            .iter()
+            .map(|p| Particle {
+                pos: self.from_local(p.pos),
+                ..*p
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn rotated() {
+    use crate::{Field, Force, Voltage};
+    use core::f32::consts::FRAC_PI_2;
+
+    // a charge above the origin
+    let particles = [Particle::new_3d(1.0, 0.1, Vec3::new(0.0, 0.0, 2.0))];
+
+    // looking straight down, the charge is off the plane
+    let field = Slice::default().local(&particles);
+    let Voltage(v) = (&field[..]).at(Vec2::ZERO);
+    assert!((v - 0.5).abs() < 1e-6);
+    let Force(e) = (&field[..]).at(Vec2::ZERO);
+    assert!(e.length() < 1e-6);
+
+    // tilted onto its side, the plane passes through the charge at y = 2
+    let slice = Slice {
+        pitch: FRAC_PI_2,
+        ..Default::default()
+    };
+    assert!(
+        slice
+            .to_space(Vec2::new(0.0, 2.0))
+            .distance(particles[0].pos)
+            < 1e-5
+    );
+    let field = slice.local(&particles);
+    let Force(e) = (&field[..]).at(Vec2::new(0.0, 3.0));
+    assert!((e - Vec2::Y).length() < 1e-5);
+}
+
+#[test]
+fn round_trip() {
+    let slice = Slice {
+        offset: 0.5,
+        yaw: 0.3,
+        pitch: -1.1,
+    };
+    let pos = Vec3::new(1.0, -2.0, 0.25);
+    assert!(slice.from_local(slice.to_local(pos)).distance(pos) < 1e-5);
+    let particles = [Particle::new_3d(1.0, 0.1, pos)];
+    let back = slice.world(&slice.local(&particles));
+    assert!(back[0].pos.distance(pos) < 1e-5);
+}
@@ -0,0 +1,63 @@
+use glam::{Mat2, Vec2, Vec3};
+
+use crate::Particle;
+
+/// A change made to a group of particles at once,
+/// about their centroid where that matters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transform {
+    Translate(Vec2),
+    /// Anticlockwise, in radians.
+    Rotate(f32),
+    /// Spreads the particles out, or draws them in, leaving their sizes alone.
+    Scale(f32),
+    /// Swaps positive charges for negative and back.
+    FlipSigns,
+}
+
+impl Transform {
+    /// Transforms every particle, in place.
+    pub fn apply(self, particles: &mut [Particle]) {
+        let centre = centroid(particles).truncate();
+        for p in particles {
+            let offset = p.pos.truncate() - centre;
+            let moved = match self {
+                Transform::Translate(by) => p.pos.truncate() + by,
+                Transform::Rotate(angle) => centre + Mat2::from_angle(angle) * offset,
+                Transform::Scale(factor) => centre + offset * factor,
+                Transform::FlipSigns => {
+                    p.value = -p.value;
+                    continue;
+                }
+            };
+            p.pos = moved.extend(p.pos.z);
+        }
+    }
+}
+
+/// The mean position of the particles, or the origin if there are none.
+pub fn centroid(particles: &[Particle]) -> Vec3 {
+    if particles.is_empty() {
+        return Vec3::ZERO;
+    }
+    particles.iter().fold(Vec3::ZERO, |sum, p| sum + p.pos) / particles.len() as f32
+}
+
+#[test]
+fn about_centroid() {
+    let mut particles = [
+        Particle::new(1.0, 0.1, Vec2::new(0.0, 0.0)),
+        Particle::new(-1.0, 0.1, Vec2::new(2.0, 0.0)),
+    ];
+    Transform::Rotate(core::f32::consts::FRAC_PI_2).apply(&mut particles);
+    assert!(particles[0].pos.distance(Vec3::new(1.0, -1.0, 0.0)) < 1e-6);
+    assert!(particles[1].pos.distance(Vec3::new(1.0, 1.0, 0.0)) < 1e-6);
+
+    Transform::Scale(2.0).apply(&mut particles);
+    assert!(particles[1].pos.distance(Vec3::new(1.0, 2.0, 0.0)) < 1e-6);
+    assert!(centroid(&particles).distance(Vec3::X) < 1e-6);
+
+    Transform::FlipSigns.apply(&mut particles);
+    assert_eq!((particles[0].value, particles[1].value), (-1.0, 1.0));
+    assert_eq!(particles[0].radius, 0.1);
+}
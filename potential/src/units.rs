@@ -0,0 +1,221 @@
+//! Quantities that know their units, so they can't be mixed up
+//! and always print with them.
+
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::scientific::si_prefix;
+
+/// Writes `x` with an SI prefix in front of `unit`,
+/// falling back on scientific notation when it's too big or small for one.
+///
+/// Three significant figures are shown unless the formatter asks for a precision.
+fn fmt_si(x: f32, unit: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // round first, so that 999.7 moves up to the next prefix
+    let x = match f.precision() {
+        None if x != 0.0 && x.is_finite() => {
+            let scale = 10f32.powi(2 - x.abs().log10().floor() as i32);
+            (x * scale).round() / scale
+        }
+        _ => x,
+    };
+    let (x, prefix) = match si_prefix(x) {
+        Some(split) => split,
+        None => return write!(f, "{:.*e} {}", f.precision().unwrap_or(2), x, unit),
+    };
+    match f.precision() {
+        Some(p) => write!(f, "{:.*} {}{}", p, x, prefix, unit),
+        None => {
+            let digits = if x == 0.0 {
+                0
+            } else {
+                (2 - x.abs().log10().floor() as i32).max(0) as usize
+            };
+            let text = format!("{:.*}", digits, x);
+            let text = match text.contains('.') {
+                true => text.trim_end_matches('0').trim_end_matches('.'),
+                false => &text,
+            };
+            write!(f, "{} {}{}", text, prefix, unit)
+        }
+    }
+}
+
+macro_rules! quantity {
+    ($(#[$meta:meta])* $name:ident, $unit:literal) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub f32);
+
+        impl $name {
+            /// The symbol of the SI unit.
+            pub const UNIT: &'static str = $unit;
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt_si(self.0, Self::UNIT, f)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+            fn mul(self, rhs: f32) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$name> for f32 {
+            type Output = $name;
+            fn mul(self, rhs: $name) -> $name {
+                $name(self * rhs.0)
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = Self;
+            fn div(self, rhs: f32) -> Self {
+                Self(self.0 / rhs)
+            }
+        }
+
+        /// The ratio of two quantities has no units.
+        impl Div for $name {
+            type Output = f32;
+            fn div(self, rhs: Self) -> f32 {
+                self.0 / rhs.0
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|q| q.0).sum())
+            }
+        }
+    };
+}
+
+/// `a * b = c`, whichever way round.
+macro_rules! product {
+    ($a:ident * $b:ident = $c:ident) => {
+        impl Mul<$b> for $a {
+            type Output = $c;
+            fn mul(self, rhs: $b) -> $c {
+                $c(self.0 * rhs.0)
+            }
+        }
+
+        impl Mul<$a> for $b {
+            type Output = $c;
+            fn mul(self, rhs: $a) -> $c {
+                $c(self.0 * rhs.0)
+            }
+        }
+
+        impl Div<$a> for $c {
+            type Output = $b;
+            fn div(self, rhs: $a) -> $b {
+                $b(self.0 / rhs.0)
+            }
+        }
+
+        impl Div<$b> for $c {
+            type Output = $a;
+            fn div(self, rhs: $b) -> $a {
+                $a(self.0 / rhs.0)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// In coulombs.
+    Charge,
+    "C"
+);
+quantity!(
+    /// In coulombs per square metre, spread over a surface.
+    SurfaceCharge,
+    "C/m²"
+);
+quantity!(
+    /// In metres.
+    Length,
+    "m"
+);
+quantity!(
+    /// In volts.
+    ElectricPotential,
+    "V"
+);
+quantity!(
+    /// In volts per metre.
+    ElectricField,
+    "V/m"
+);
+quantity!(
+    /// In joules.
+    Energy,
+    "J"
+);
+
+product!(Charge * ElectricPotential = Energy);
+product!(ElectricField * Length = ElectricPotential);
+
+#[test]
+fn prefixes() {
+    assert_eq!(Charge(2.5e-6).to_string(), "2.5 µC");
+    assert_eq!(ElectricField(-12_000.0).to_string(), "-12 kV/m");
+    assert_eq!(ElectricPotential(8.987_552e9).to_string(), "8.99 GV");
+    assert_eq!(SurfaceCharge(-3e-6).to_string(), "-3 µC/m²");
+    assert_eq!(Length(0.0).to_string(), "0 m");
+    assert_eq!(Length(1.0).to_string(), "1 m");
+    assert_eq!(ElectricPotential(999.7).to_string(), "1 kV");
+    assert_eq!(Energy(1.5e-20).to_string(), "1.50e-20 J");
+    assert_eq!(Charge(f32::INFINITY).to_string(), "inf C");
+    assert_eq!(format!("{:.1}", Length(0.25)), "250.0 mm");
+
+    let e = ElectricPotential(12.0) / Length(3.0);
+    assert_eq!(e, ElectricField(4.0));
+    assert_eq!(Charge(2.0) * (e * Length(0.5)), Energy(4.0));
+    assert_eq!(
+        [Length(1.0), Length(2.0)].into_iter().sum::<Length>(),
+        Length(3.0)
+    );
+}